@@ -0,0 +1,101 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use crate::error::AdgError;
+use crate::interpreter::Value;
+
+pub type EnvRef = Rc<RefCell<Environment>>;
+
+/// Variável declarada em um escopo léxico.
+#[derive(Debug, Clone)]
+struct Binding {
+    value: Value,
+    is_const: bool,
+}
+
+/// Um quadro da cadeia de escopos. Closures guardam uma referência (não uma cópia)
+/// ao quadro onde foram definidas, então enxergam alterações feitas depois.
+#[derive(Default)]
+pub struct Environment {
+    vars: HashMap<String, Binding>,
+    parent: Option<EnvRef>,
+}
+
+impl Environment {
+    pub fn new() -> EnvRef {
+        Rc::new(RefCell::new(Environment::default()))
+    }
+
+    pub fn child(parent: &EnvRef) -> EnvRef {
+        Rc::new(RefCell::new(Environment {
+            vars: HashMap::new(),
+            parent: Some(Rc::clone(parent)),
+        }))
+    }
+
+    pub fn declare(&mut self, name: String, value: Value, is_const: bool) -> Result<(), AdgError> {
+        if self.vars.contains_key(&name) {
+            return Err(AdgError::name_error(format!("Variable '{}' already declared in this scope", name)));
+        }
+        self.vars.insert(name, Binding { value, is_const });
+        Ok(())
+    }
+
+    // Declaração que substitui uma anterior do mesmo nome, como no topo do REPL
+    pub fn redeclare(&mut self, name: String, value: Value, is_const: bool) {
+        self.vars.insert(name, Binding { value, is_const });
+    }
+
+    // Declaração usada pelo próprio runtime (nativos, parâmetros), sem checar duplicatas
+    pub fn define(&mut self, name: String, value: Value) {
+        self.vars.insert(name, Binding { value, is_const: false });
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        match self.vars.get(name) {
+            Some(binding) => Some(binding.value.clone()),
+            None => self.parent.as_ref()?.borrow().get(name),
+        }
+    }
+
+    pub fn assign(&mut self, name: &str, value: Value) -> Result<(), AdgError> {
+        if let Some(binding) = self.vars.get_mut(name) {
+            if binding.is_const {
+                return Err(AdgError::type_error(format!("Assignment to constant variable '{}'", name)));
+            }
+            binding.value = value;
+            return Ok(());
+        }
+
+        match &self.parent {
+            Some(parent) => parent.borrow_mut().assign(name, value),
+            None => Err(AdgError::name_error(format!("Assignment to undeclared variable '{}'", name))),
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.vars.contains_key(name)
+    }
+
+    /// Variáveis declaradas neste quadro (sem as dos pais), em ordem alfabética.
+    pub fn bindings(&self) -> Vec<(String, Value)> {
+        let mut bindings: Vec<(String, Value)> = self.vars.iter()
+            .map(|(name, binding)| (name.clone(), binding.value.clone()))
+            .collect();
+        bindings.sort_by(|a, b| a.0.cmp(&b.0));
+        bindings
+    }
+}
+
+// Funções guardam o próprio quadro onde vivem, então um Debug derivado entraria em loop
+impl fmt::Debug for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&String> = self.vars.keys().collect();
+        names.sort();
+        f.debug_struct("Environment")
+            .field("vars", &names)
+            .field("has_parent", &self.parent.is_some())
+            .finish()
+    }
+}
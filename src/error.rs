@@ -0,0 +1,165 @@
+use std::fmt;
+use crate::interpreter::Value;
+use crate::span::{render, SourceMap, Span};
+
+/// Erro da linguagem, com o trecho do código onde ocorreu (quando conhecido).
+#[derive(Debug, Clone)]
+pub struct AdgError {
+    pub kind: ErrorKind,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ErrorKind {
+    /// Literal malformado (número, string, comentário sem fim...).
+    Lex(String),
+    /// Erro de sintaxe.
+    Parse(String),
+    Type(String),
+    Name(String),
+    Index(String),
+    Arity(String),
+    Io(String),
+    /// Módulo inexistente, importação circular ou nome não exportado.
+    Import(String),
+    /// Chamadas aninhadas demais (recursão sem fim, em geral).
+    Recursion(String),
+    /// Nenhum braço do `match` casou com o valor.
+    Match(String),
+    /// Valor lançado com `throw`.
+    Thrown(Value),
+}
+
+impl AdgError {
+    pub fn new(kind: ErrorKind) -> Self {
+        AdgError { kind, span: None }
+    }
+
+    pub fn lex(message: impl Into<String>, span: Span) -> Self {
+        AdgError { kind: ErrorKind::Lex(message.into()), span: Some(span) }
+    }
+
+    pub fn parse(message: impl Into<String>, span: Span) -> Self {
+        AdgError { kind: ErrorKind::Parse(message.into()), span: Some(span) }
+    }
+
+    pub fn syntax_error(message: impl Into<String>) -> Self {
+        AdgError::new(ErrorKind::Parse(message.into()))
+    }
+
+    pub fn type_error(message: impl Into<String>) -> Self {
+        AdgError::new(ErrorKind::Type(message.into()))
+    }
+
+    pub fn name_error(message: impl Into<String>) -> Self {
+        AdgError::new(ErrorKind::Name(message.into()))
+    }
+
+    pub fn index_error(message: impl Into<String>) -> Self {
+        AdgError::new(ErrorKind::Index(message.into()))
+    }
+
+    pub fn arity_error(message: impl Into<String>) -> Self {
+        AdgError::new(ErrorKind::Arity(message.into()))
+    }
+
+    pub fn io_error(message: impl Into<String>) -> Self {
+        AdgError::new(ErrorKind::Io(message.into()))
+    }
+
+    pub fn import_error(message: impl Into<String>) -> Self {
+        AdgError::new(ErrorKind::Import(message.into()))
+    }
+
+    pub fn recursion_error(message: impl Into<String>) -> Self {
+        AdgError::new(ErrorKind::Recursion(message.into()))
+    }
+
+    pub fn match_error(message: impl Into<String>) -> Self {
+        AdgError::new(ErrorKind::Match(message.into()))
+    }
+
+    pub fn thrown(value: Value) -> Self {
+        AdgError::new(ErrorKind::Thrown(value))
+    }
+
+    // Guarda só o trecho mais interno: é ali que o erro de fato aconteceu
+    pub fn at(mut self, span: Span) -> Self {
+        self.span.get_or_insert(span);
+        self
+    }
+
+    /// Nome do tipo do erro, o mesmo exposto em `e.kind` no `catch`.
+    pub fn kind_name(&self) -> String {
+        match &self.kind {
+            ErrorKind::Lex(_) | ErrorKind::Parse(_) => "SyntaxError".to_string(),
+            ErrorKind::Type(_) => "TypeError".to_string(),
+            ErrorKind::Name(_) => "NameError".to_string(),
+            ErrorKind::Index(_) => "IndexError".to_string(),
+            ErrorKind::Arity(_) => "ArityError".to_string(),
+            ErrorKind::Io(_) => "IOError".to_string(),
+            ErrorKind::Import(_) => "ImportError".to_string(),
+            ErrorKind::Recursion(_) => "RecursionError".to_string(),
+            ErrorKind::Match(_) => "MatchError".to_string(),
+            ErrorKind::Thrown(Value::Error { kind, .. }) => kind.clone(),
+            ErrorKind::Thrown(value) => value.type_name().to_string(),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match &self.kind {
+            ErrorKind::Lex(message)
+            | ErrorKind::Parse(message)
+            | ErrorKind::Type(message)
+            | ErrorKind::Name(message)
+            | ErrorKind::Index(message)
+            | ErrorKind::Arity(message)
+            | ErrorKind::Io(message)
+            | ErrorKind::Import(message)
+            | ErrorKind::Recursion(message)
+            | ErrorKind::Match(message) => message.clone(),
+            ErrorKind::Thrown(Value::Error { message, .. }) => message.clone(),
+            ErrorKind::Thrown(value) => value.as_string(),
+        }
+    }
+
+    /// Mensagem para o usuário; com um trecho conhecido, inclui `arquivo:linha:coluna`
+    /// e a linha do código sublinhada.
+    pub fn report(&self, path: &str, source: &str) -> String {
+        match self.span {
+            Some(span) => render(path, source, span, &self.to_string()),
+            None => format!("erro: {}", self),
+        }
+    }
+
+    /// Como `report`, mas procura o arquivo do trecho entre os já carregados
+    /// (necessário quando o erro pode vir de um módulo importado).
+    pub fn report_in(&self, sources: &SourceMap) -> String {
+        match self.span.and_then(|span| sources.locate(span)) {
+            Some((path, source, span)) => render(path, source, span, &self.to_string()),
+            None => format!("erro: {}", self),
+        }
+    }
+
+    /// Valor recebido pelo `catch`: o próprio valor lançado, ou um `Value::Error`.
+    pub fn value(&self) -> Value {
+        match &self.kind {
+            ErrorKind::Thrown(value) => value.clone(),
+            _ => Value::Error { kind: self.kind_name(), message: self.message() },
+        }
+    }
+}
+
+impl fmt::Display for AdgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            // Qualquer valor pode ser lançado; só erros têm um tipo para mostrar
+            ErrorKind::Thrown(value) if !matches!(value, Value::Error { .. }) => {
+                write!(f, "Uncaught {}", value.as_string())
+            }
+            _ => write!(f, "{}: {}", self.kind_name(), self.message()),
+        }
+    }
+}
+
+impl std::error::Error for AdgError {}
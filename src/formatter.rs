@@ -0,0 +1,404 @@
+use crate::error::AdgError;
+use crate::lexer::{Lexer, Token};
+use crate::parser::Parser;
+
+const INDENT: &str = "    ";
+
+/// Formata o código do `adg fmt`. Só o espaço em branco muda: a indentação é
+/// recalculada pela profundidade de `{ ( [` e os espaços entre tokens de uma mesma
+/// linha são normalizados. Quebras de linha (até uma linha em branco seguida),
+/// literais e comentários ficam como no original.
+pub fn format(source: &str) -> Result<String, Vec<AdgError>> {
+    // Código com erro de sintaxe não é tocado
+    Parser::new(Lexer::new(source)).parse()?;
+
+    let mut out = String::new();
+    let mut depth = 0usize;
+    // Profundidades onde há um `case`/`default` aberto: o corpo ganha um nível a mais
+    let mut cases: Vec<usize> = vec![];
+    // Último token de código já escrito (comentários não contam)
+    let mut last: Option<Token> = None;
+
+    for line in lines(source) {
+        if line.blank_before && !out.is_empty() {
+            out.push('\n');
+        }
+
+        let closers = line.pieces.iter()
+            .take_while(|piece| matches!(piece.token, Some(Token::Symbol('}' | ')' | ']'))))
+            .count();
+        let effective = depth.saturating_sub(closers);
+        let first = line.pieces[0].token.as_ref();
+
+        cases.retain(|&case_depth| case_depth <= effective);
+        if matches!(first, Some(Token::Case | Token::Default)) {
+            cases.retain(|&case_depth| case_depth < effective);
+        }
+
+        // Linha que continua a anterior: `if (x)` sem chaves, `.metodo()`, `a +`
+        let continues = closers == 0
+            && !matches!(first, Some(Token::Symbol('{')))
+            && last.as_ref().is_some_and(|token| !ends_line(token));
+
+        let level = effective + cases.len() + continues as usize;
+        for _ in 0..level {
+            out.push_str(INDENT);
+        }
+
+        let mut previous: Option<&Piece> = None;
+        for piece in &line.pieces {
+            if let Some(previous) = previous
+                && spaced(previous, piece)
+            {
+                out.push(' ');
+            }
+            out.push_str(piece.text);
+
+            if let Some(token) = &piece.token {
+                match token {
+                    Token::Symbol('{' | '(' | '[') => depth += 1,
+                    Token::Symbol('}' | ')' | ']') => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                last = Some(token.clone());
+            }
+            previous = Some(piece);
+        }
+
+        // `case 1: {` já indenta o corpo pelas chaves
+        if matches!(first, Some(Token::Case | Token::Default)) && last != Some(Token::Symbol('{')) {
+            cases.push(effective);
+        }
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+/// Token ou comentário, com o texto exatamente como está no original.
+struct Piece<'a> {
+    text: &'a str,
+    // `None` para comentários
+    token: Option<Token>,
+    // Se o token é um operador prefixo (`-x`, `!x`, `++x`)
+    prefix: bool,
+}
+
+struct Line<'a> {
+    pieces: Vec<Piece<'a>>,
+    blank_before: bool,
+}
+
+// Separa o código em linhas de tokens e comentários, como no original
+fn lines(source: &str) -> Vec<Line<'_>> {
+    let mut lines: Vec<Line> = vec![];
+    let mut newlines = 0;
+
+    let mut lexer = Lexer::new(source);
+    let mut end = 0;
+    let mut operand = false;
+    loop {
+        let token = lexer.next_token();
+        let span = lexer.span();
+
+        // Entre dois tokens só há espaço em branco e comentários
+        let gap = &source[end..span.start];
+        let mut rest = gap;
+        while let Some(c) = rest.chars().next() {
+            if c == '\n' {
+                newlines += 1;
+                rest = &rest[1..];
+            } else if c.is_whitespace() {
+                rest = &rest[c.len_utf8()..];
+            } else {
+                let len = comment_len(rest);
+                push(&mut lines, &mut newlines, Piece { text: &rest[..len], token: None, prefix: false });
+                rest = &rest[len..];
+            }
+        }
+
+        if token == Token::EOF {
+            break;
+        }
+
+        // Palavra reservada usada como nome (`obj.default`, `{ case: 1 }`) é formatada
+        // como um identificador, e não abre um `case`
+        let token = match token.keyword_name() {
+            Some(name) if last_token(&lines) == Some(&Token::Symbol('.')) => Token::Identifier(name.to_string()),
+            _ => token,
+        };
+        if token == Token::Symbol(':') {
+            rename_key(&mut lines);
+        }
+
+        let prefix = !operand && matches!(&token, Token::Operator(op) if matches!(op.as_str(), "-" | "+" | "!" | "++" | "--"));
+        operand = match &token {
+            Token::Identifier(_) | Token::Number(_) | Token::String(_) | Token::Template(_) | Token::Boolean(_) | Token::Null => true,
+            Token::Symbol(')' | ']' | '}') => true,
+            // `x++` continua sendo um operando
+            Token::Operator(op) if op == "++" || op == "--" => operand,
+            _ => false,
+        };
+
+        push(&mut lines, &mut newlines, Piece { text: &source[span.start..span.end], token: Some(token), prefix });
+        end = span.end;
+    }
+    lines
+}
+
+fn last_token<'a>(lines: &'a [Line]) -> Option<&'a Token> {
+    lines.iter().rev()
+        .flat_map(|line| line.pieces.iter().rev())
+        .find_map(|piece| piece.token.as_ref())
+}
+
+// Antes de um `:`, uma palavra reservada logo depois de `,` ou de um `{` que abre
+// um objeto é uma chave, não um `default:`/`case` (o `{` de um switch vem após `)`)
+fn rename_key(lines: &mut [Line]) {
+    let mut tokens = lines.iter_mut().rev()
+        .flat_map(|line| line.pieces.iter_mut().rev())
+        .filter_map(|piece| piece.token.as_mut());
+    let (Some(key), Some(before)) = (tokens.next(), tokens.next()) else {
+        return;
+    };
+    let is_key = match before {
+        Token::Symbol(',') => true,
+        Token::Symbol('{') => tokens.next() != Some(&mut Token::Symbol(')')),
+        _ => false,
+    };
+    if is_key && let Some(name) = key.keyword_name() {
+        *key = Token::Identifier(name.to_string());
+    }
+}
+
+// Junta a peça à linha atual, ou começa uma nova se houve quebra de linha antes dela
+fn push<'a>(lines: &mut Vec<Line<'a>>, newlines: &mut usize, piece: Piece<'a>) {
+    match lines.last_mut() {
+        Some(line) if *newlines == 0 => line.pieces.push(piece),
+        _ => lines.push(Line { pieces: vec![piece], blank_before: *newlines > 1 }),
+    }
+    *newlines = 0;
+}
+
+// Tamanho do comentário no início de `text` (`//` até o fim da linha, ou `/* */` aninhado)
+fn comment_len(text: &str) -> usize {
+    if text.starts_with("//") {
+        return text.find('\n').unwrap_or(text.len());
+    }
+
+    let mut depth = 0;
+    let mut i = 0;
+    while i < text.len() {
+        if text[i..].starts_with("/*") {
+            depth += 1;
+            i += 2;
+        } else if text[i..].starts_with("*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += text[i..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    text.len()
+}
+
+// Depois destes tokens, a próxima linha começa um statement ou elemento novo
+fn ends_line(token: &Token) -> bool {
+    matches!(token, Token::Symbol(';' | '{' | '}' | ',' | '(' | '[' | ':'))
+}
+
+fn spaced(previous: &Piece, next: &Piece) -> bool {
+    let (Some(prev), Some(token)) = (&previous.token, &next.token) else {
+        // Comentários ficam separados por um espaço do que vem ao lado
+        return true;
+    };
+
+    let glued = match (prev, token) {
+        // Resto num padrão: `[a, ...resto]`
+        (Token::Symbol(','), Token::Symbol('.')) => false,
+        (_, Token::Symbol(';' | ',' | ')' | ']' | '.' | ':')) => true,
+        (Token::Symbol('(' | '[' | '.'), _) => true,
+        (Token::Symbol('{'), Token::Symbol('}')) => true,
+        (Token::Operator(_), _) if previous.prefix => true,
+        // Sufixo: `i++`
+        (_, Token::Operator(op)) if (op == "++" || op == "--") && !next.prefix => true,
+        // Chamada e indexação: `f(x)`, `lista[0]`
+        (
+            Token::Identifier(_) | Token::String(_) | Token::Template(_) | Token::Symbol(')' | ']'),
+            Token::Symbol('(' | '['),
+        ) => true,
+        _ => false,
+    };
+
+    // Nunca junta dois tokens que, colados, seriam lidos de outro jeito (`- -x`)
+    !glued || fuses(previous.text, next.text)
+}
+
+fn fuses(left: &str, right: &str) -> bool {
+    let mut lexer = Lexer::new(&format!("{}{}", left, right));
+    lexer.next_token();
+    lexer.span().end != left.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(source: &str) -> String {
+        let formatted = format(source).unwrap_or_else(|errors| panic!("{}", errors[0]));
+        assert_eq!(format(&formatted).unwrap(), formatted, "formatting should be idempotent");
+        formatted
+    }
+
+    #[test]
+    fn reindents_blocks_and_normalizes_spacing() {
+        let source = "\
+fn   soma(a,b){
+return a+b ;
+}
+if(soma(1,2)>2){
+      print( \"ok\" );
+}else{
+print(-soma(1,-2),!true);
+}
+for(let i=0;i<3;i++){print(i);}
+let o={a:1,b:[1,2]};
+let f=(x)=>x*2;
+let g=fn(x){return x;};
+";
+        assert_eq!(fmt(source), "\
+fn soma(a, b) {
+    return a + b;
+}
+if (soma(1, 2) > 2) {
+    print(\"ok\");
+} else {
+    print(-soma(1, -2), !true);
+}
+for (let i = 0; i < 3; i++) { print(i); }
+let o = { a: 1, b: [1, 2] };
+let f = (x) => x * 2;
+let g = fn (x) { return x; };
+");
+    }
+
+    #[test]
+    fn keeps_comments_literals_and_blank_lines() {
+        let source = "\n\n\
+/// Documentação
+let   x = 0xFF_FF;   // hexadecimal
+/* bloco
+   em várias linhas */
+
+
+
+let s = 'aspas'+`t ${ x }`;
+let y = x- -1;
+";
+        assert_eq!(fmt(source), "\
+/// Documentação
+let x = 0xFF_FF; // hexadecimal
+/* bloco
+   em várias linhas */
+
+let s = 'aspas' + `t ${ x }`;
+let y = x - -1;
+");
+    }
+
+    #[test]
+    fn indents_switch_cases_and_continuation_lines() {
+        let source = "\
+switch (x) {
+case 1:
+print(1);
+break;
+default: {
+print(2);
+}
+}
+if (x)
+print(x);
+let total = 1 +
+2;
+let lista = [
+1,
+2
+];
+";
+        assert_eq!(fmt(source), "\
+switch (x) {
+    case 1:
+        print(1);
+        break;
+    default: {
+        print(2);
+    }
+}
+if (x)
+    print(x);
+let total = 1 +
+    2;
+let lista = [
+    1,
+    2
+];
+");
+    }
+
+    #[test]
+    fn keywords_used_as_property_names() {
+        let source = "\
+let o = {
+default: 1,
+case: 2,
+match: p.match (1)
+};
+switch (x) {
+default:
+print(o . default);
+}
+";
+        assert_eq!(fmt(source), "\
+let o = {
+    default: 1,
+    case: 2,
+    match: p.match(1)
+};
+switch (x) {
+    default:
+        print(o.default);
+}
+");
+    }
+
+    #[test]
+    fn formats_match_arms_and_patterns() {
+        let source = "\
+let r = match (v) {
+[a,...resto] if (a>0) => resto,
+Shape.Rect(w,h) => {
+w*h
+},
+_ => null
+};
+";
+        assert_eq!(fmt(source), "\
+let r = match (v) {
+    [a, ...resto] if (a > 0) => resto,
+    Shape.Rect(w, h) => {
+        w * h
+    },
+    _ => null
+};
+");
+    }
+
+    #[test]
+    fn refuses_code_with_syntax_errors() {
+        assert!(format("let = 1;").is_err());
+    }
+}
@@ -1,9 +1,42 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
-use crate::parser::{Expr, Literal, Stmt};
+use std::fs;
+use std::mem;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+use indexmap::IndexMap;
+use crate::environment::{EnvRef, Environment};
+use crate::error::AdgError;
+use crate::lexer::Lexer;
+use crate::parser::{
+    Expr, ExprKind, Field, ImportClause, Literal, MatchArm, Method, Parser, Pattern, Stmt, StmtKind, SwitchCase, Variant,
+};
+use crate::span::{render_as, SourceMap, Span};
 use crate::runtime::math::get_math_module;
 use crate::runtime::style::get_style_module; // 👈 Adicionado
+use crate::runtime::geo::get_geo_module;
+use crate::runtime::qr::get_qr_module;
 use crate::runtime::filebox::get_filebox_module;
 
+pub type NativeFn = fn(Vec<Value>) -> Result<Value, AdgError>;
+
+/// Propriedades de um objeto, na ordem de inserção. Objetos são compartilhados por referência.
+pub type ObjectRef = Rc<RefCell<IndexMap<String, Value>>>;
+
+/// Elementos de um array, também compartilhados por referência.
+pub type ArrayRef = Rc<RefCell<Vec<Value>>>;
+
+/// Máximo de chamadas aninhadas (funções e construtores); além disso, `RecursionError`.
+pub const MAX_CALL_DEPTH: usize = 2000;
+
+// Pilha nativa reservada por chamada da linguagem. Uma chamada cujo corpo aninha `match`,
+// `for` e `try` ocupa cerca de 12 KiB no build de release e 96 KiB no de debug
+const FRAME_SIZE: usize = if cfg!(debug_assertions) { 128 * 1024 } else { 32 * 1024 };
+
+/// Pilha da thread que roda o interpretador: o bastante para `MAX_CALL_DEPTH` chamadas,
+/// para que o limite de chamadas, e não a pilha nativa, interrompa uma recursão profunda.
+pub const STACK_SIZE: usize = MAX_CALL_DEPTH * FRAME_SIZE;
+
 #[derive(Debug, Clone)]
 pub enum Value {
     Number(f64),
@@ -12,29 +45,113 @@ pub enum Value {
     Null,
     Function {
         params: Vec<String>,
-        body: Vec<Stmt>,
-        env: Env,
+        body: Rc<[Stmt]>,
+        env: EnvRef,
+    },
+    NativeFunction(NativeFn),
+    Array(ArrayRef),
+    Object(ObjectRef),
+    // O próprio `struct`: chamá-lo cria uma instância
+    Struct(Rc<StructDef>),
+    Instance(Rc<Instance>),
+    Enum(Rc<EnumDef>),
+    // Valor de um enum: a variante (pelo índice na declaração) e o que ela carrega
+    Variant {
+        def: Rc<EnumDef>,
+        index: usize,
+        payload: Vec<Value>,
+    },
+    // `Shape.Circle` antes de receber os valores
+    Constructor {
+        def: Rc<EnumDef>,
+        index: usize,
     },
-    NativeFunction(fn(Vec<Value>) -> Value),
-    Array(Vec<Value>),
+    Error {
+        kind: String,
+        message: String,
+    },
+}
+
+/// Declaração de um `struct`, junto com o quadro onde foi feita: é nele que os
+/// valores padrão dos campos são avaliados e que os métodos enxergam as variáveis.
+#[derive(Debug)]
+pub struct StructDef {
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub methods: Vec<Method>,
+    env: EnvRef,
+}
+
+/// Instância de um `struct`. Os campos são fixos (os da declaração) e, como nos
+/// objetos, compartilhados por referência.
+#[derive(Debug)]
+pub struct Instance {
+    pub def: Rc<StructDef>,
+    pub fields: ObjectRef,
+}
+
+/// Declaração de um `enum`.
+#[derive(Debug)]
+pub struct EnumDef {
+    pub name: String,
+    pub variants: Vec<Variant>,
+}
+
+impl EnumDef {
+    fn variant(&self, name: &str) -> Option<usize> {
+        self.variants.iter().position(|variant| variant.name == name)
+    }
+}
+
+impl StructDef {
+    fn method(&self, name: &str) -> Option<&Method> {
+        self.methods.iter().find(|method| method.name == name)
+    }
+
+    fn has_field(&self, name: &str) -> bool {
+        self.fields.iter().any(|field| field.name == name)
+    }
+}
+
+impl Instance {
+    // Campo que pode receber atribuição; não é possível criar campos novos
+    fn field_place(&self, name: String) -> Result<Place, AdgError> {
+        if !self.def.has_field(&name) {
+            return Err(AdgError::type_error(format!("{} has no field '{}'", self.def.name, name)));
+        }
+        Ok(Place::Property(Rc::clone(&self.fields), name))
+    }
+}
+
+// Método ligado à instância: `this` (e o sinônimo `self`) ficam no quadro da definição
+fn bind_method(instance: &Value, def: &StructDef, method: &Method) -> Value {
+    let frame = Environment::child(&def.env);
+    frame.borrow_mut().define("this".to_string(), instance.clone());
+    frame.borrow_mut().define("self".to_string(), instance.clone());
+    Value::Function { params: method.params.clone(), body: Rc::clone(&method.body), env: frame }
 }
 
 impl Value {
-    pub fn as_number(&self) -> f64 {
+    pub fn as_number(&self) -> Result<f64, AdgError> {
         match self {
-            Value::Number(n) => *n,
-            _ => panic!("Expected number, got {:?}", self),
+            Value::Number(n) => Ok(*n),
+            _ => Err(AdgError::type_error(format!("Expected number, got {}", self.type_name()))),
         }
     }
 
-    pub fn as_bool(&self) -> bool {
+    pub fn as_bool(&self) -> Result<bool, AdgError> {
         match self {
-            Value::Boolean(b) => *b,
-            _ => panic!("Expected boolean, got {:?}", self),
+            Value::Boolean(b) => Ok(*b),
+            _ => Err(AdgError::type_error(format!("Expected boolean, got {}", self.type_name()))),
         }
     }
 
     pub fn as_string(&self) -> String {
+        self.display(&mut vec![])
+    }
+
+    // `seen` guarda os objetos e arrays sendo mostrados agora: reencontrar um deles é um ciclo
+    fn display(&self, seen: &mut Vec<*const ()>) -> String {
         match self {
             Value::String(s) => s.clone(),
             Value::Number(n) => {
@@ -46,33 +163,304 @@ impl Value {
             }
             Value::Boolean(b) => b.to_string(),
             Value::Null => "null".to_string(),
+            // Um objeto ou array que contém a si mesmo (direta ou indiretamente) aparece como `[Circular]`
+            Value::Array(arr) if seen.contains(&shared_ptr(arr)) => "[Circular]".to_string(),
             Value::Array(arr) => {
-                let items: Vec<String> = arr.iter().map(|v| v.as_string()).collect();
+                seen.push(shared_ptr(arr));
+                let items: Vec<String> = arr.borrow().iter().map(|v| v.display(seen)).collect();
+                seen.pop();
                 format!("[{}]", items.join(", "))
             }
+            Value::Object(obj) if seen.contains(&shared_ptr(obj)) => "[Circular]".to_string(),
+            Value::Instance(instance) if seen.contains(&shared_ptr(&instance.fields)) => "[Circular]".to_string(),
+            Value::Object(obj) => display_entries(obj, seen).unwrap_or_else(|| "{}".to_string()),
+            Value::Instance(instance) => match display_entries(&instance.fields, seen) {
+                Some(entries) => format!("{} {}", instance.def.name, entries),
+                None => format!("{} {{}}", instance.def.name),
+            },
+            Value::Struct(def) => format!("[Struct {}]", def.name),
+            Value::Variant { def, index, payload } => {
+                let name = format!("{}.{}", def.name, def.variants[*index].name);
+                if payload.is_empty() {
+                    return name;
+                }
+                let items: Vec<String> = payload.iter().map(|v| v.display(seen)).collect();
+                format!("{}({})", name, items.join(", "))
+            }
+            Value::Constructor { def, index } => format!("[Variant {}.{}]", def.name, def.variants[*index].name),
+            Value::Enum(def) => format!("[Enum {}]", def.name),
             Value::Function { .. } => "[Function]".to_string(),
             Value::NativeFunction(_) => "[NativeFunction]".to_string(),
+            Value::Error { kind, message } => format!("{}: {}", kind, message),
+        }
+    }
+
+    pub fn array(items: Vec<Value>) -> Value {
+        Value::Array(Rc::new(RefCell::new(items)))
+    }
+
+    pub fn object(entries: IndexMap<String, Value>) -> Value {
+        Value::Object(Rc::new(RefCell::new(entries)))
+    }
+
+    // Acesso a `valor.nome`; propriedades ausentes de um objeto valem null,
+    // mas numa instância são um erro
+    pub fn get_member(&self, name: &str) -> Result<Value, AdgError> {
+        match (self, name) {
+            (Value::Object(obj), _) => Ok(obj.borrow().get(name).cloned().unwrap_or(Value::Null)),
+            (Value::Instance(instance), _) => {
+                if let Some(value) = instance.fields.borrow().get(name) {
+                    return Ok(value.clone());
+                }
+                match instance.def.method(name) {
+                    Some(method) => Ok(bind_method(self, &instance.def, method)),
+                    None => Err(AdgError::type_error(
+                        format!("{} has no field or method '{}'", instance.def.name, name),
+                    )),
+                }
+            }
+            // Variantes sem valores já são o próprio valor; as outras são construtores
+            (Value::Enum(def), _) => match def.variant(name) {
+                Some(index) if def.variants[index].fields.is_empty() => {
+                    Ok(Value::Variant { def: Rc::clone(def), index, payload: vec![] })
+                }
+                Some(index) => Ok(Value::Constructor { def: Rc::clone(def), index }),
+                None => Err(AdgError::type_error(format!("{} has no variant '{}'", def.name, name))),
+            },
+            (Value::Variant { def, index, payload }, _) => {
+                let variant = &def.variants[*index];
+                match variant.fields.iter().position(|field| field == name) {
+                    Some(i) => Ok(payload[i].clone()),
+                    None => Err(AdgError::type_error(
+                        format!("{}.{} has no field '{}'", def.name, variant.name, name),
+                    )),
+                }
+            }
+            (Value::Error { kind, .. }, "kind") => Ok(Value::String(kind.clone())),
+            (Value::Error { message, .. }, "message") => Ok(Value::String(message.clone())),
+            _ => Err(AdgError::type_error(
+                format!("Cannot read property '{}' of {}", name, self.type_name()),
+            )),
         }
-    }    
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Boolean(_) => "boolean",
+            Value::Null => "null",
+            Value::Function { .. } | Value::NativeFunction(_) | Value::Constructor { .. } => "function",
+            Value::Array(_) => "array",
+            Value::Object(_) | Value::Instance(_) => "object",
+            Value::Struct(_) => "struct",
+            Value::Enum(_) => "enum",
+            Value::Variant { .. } => "variant",
+            Value::Error { .. } => "error",
+        }
+    }
+}
+
+// "1 argument", "2 arguments"
+fn arguments(count: usize) -> String {
+    format!("{} argument{}", count, if count == 1 { "" } else { "s" })
+}
+
+// Identidade de um objeto ou array compartilhado, para detectar ciclos
+fn shared_ptr<T>(value: &Rc<RefCell<T>>) -> *const () {
+    Rc::as_ptr(value) as *const ()
+}
+
+// `{ a: 1, b: 2 }`, ou `None` para um objeto vazio
+fn display_entries(obj: &ObjectRef, seen: &mut Vec<*const ()>) -> Option<String> {
+    let entries = obj.borrow();
+    if entries.is_empty() {
+        return None;
+    }
+    seen.push(shared_ptr(obj));
+    let entries: Vec<String> = entries.iter()
+        .map(|(k, v)| format!("{}: {}", k, v.display(seen)))
+        .collect();
+    seen.pop();
+    Some(format!("{{ {} }}", entries.join(", ")))
+}
+
+// Pares de objetos ou arrays sendo comparados agora. Reencontrar um par é um ciclo, que conta como
+// igual: se houver alguma diferença, ela aparece em outro ponto da comparação
+type Visiting = Vec<(*const (), *const ())>;
+
+fn objects_equal(a: &ObjectRef, b: &ObjectRef, visiting: &mut Visiting) -> bool {
+    let pair = (shared_ptr(a), shared_ptr(b));
+    if Rc::ptr_eq(a, b) || visiting.contains(&pair) {
+        return true;
+    }
+    visiting.push(pair);
+    let (a, b) = (a.borrow(), b.borrow());
+    let equal = a.len() == b.len()
+        && a.iter().all(|(key, value)| b.get(key).is_some_and(|other| value.equals(other, visiting)));
+    visiting.pop();
+    equal
+}
+
+fn arrays_equal(a: &ArrayRef, b: &ArrayRef, visiting: &mut Visiting) -> bool {
+    let pair = (shared_ptr(a), shared_ptr(b));
+    if Rc::ptr_eq(a, b) || visiting.contains(&pair) {
+        return true;
+    }
+    visiting.push(pair);
+    let (a, b) = (a.borrow(), b.borrow());
+    let equal = a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| x.equals(y, visiting));
+    visiting.pop();
+    equal
 }
 
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
+        self.equals(other, &mut vec![])
+    }
+}
+
+impl Value {
+    fn equals(&self, other: &Value, visiting: &mut Visiting) -> bool {
         match (self, other) {
             (Value::Number(a), Value::Number(b)) => a == b,
             (Value::String(a), Value::String(b)) => a == b,
             (Value::Boolean(a), Value::Boolean(b)) => a == b,
             (Value::Null, Value::Null) => true,
+            (Value::Array(a), Value::Array(b)) => arrays_equal(a, b, visiting),
+            (Value::Object(a), Value::Object(b)) => objects_equal(a, b, visiting),
+            (Value::Struct(a), Value::Struct(b)) => Rc::ptr_eq(a, b),
+            (Value::Enum(a), Value::Enum(b)) => Rc::ptr_eq(a, b),
+            (
+                Value::Variant { def: da, index: ia, payload: pa },
+                Value::Variant { def: db, index: ib, payload: pb },
+            ) => {
+                Rc::ptr_eq(da, db) && ia == ib && pa.iter().zip(pb).all(|(x, y)| x.equals(y, visiting))
+            }
+            (
+                Value::Constructor { def: da, index: ia },
+                Value::Constructor { def: db, index: ib },
+            ) => Rc::ptr_eq(da, db) && ia == ib,
+            // Instâncias do mesmo struct são iguais quando os campos são iguais
+            (Value::Instance(a), Value::Instance(b)) => {
+                Rc::ptr_eq(&a.def, &b.def) && objects_equal(&a.fields, &b.fields, visiting)
+            }
+            (
+                Value::Error { kind: ka, message: ma },
+                Value::Error { kind: kb, message: mb },
+            ) => ka == kb && ma == mb,
             _ => false,
         }
     }
 }
 
-type Env = HashMap<String, Value>;
+/// Local que pode receber atribuição, já resolvido (objeto e índice avaliados uma única vez).
+enum Place {
+    Variable(String),
+    Property(ObjectRef, String),
+    Element(ArrayRef, usize),
+}
+
+// Índice de array válido: inteiro e não negativo
+fn array_index(index: &Value) -> Result<usize, AdgError> {
+    let index = index.as_number()?;
+    if index < 0.0 || index.fract() != 0.0 {
+        return Err(AdgError::index_error(format!("Invalid array index {}", index)));
+    }
+    Ok(index as usize)
+}
+
+// Falha de `assert`/`assert_eq`, capturável como `e.kind == "AssertionError"`
+fn assertion_error(message: String) -> AdgError {
+    AdgError::thrown(Value::Error { kind: "AssertionError".to_string(), message })
+}
+
+fn binary_op(op: &str, left: Value, right: Value) -> Result<Value, AdgError> {
+    let value = match op {
+        "+" => match (left, right) {
+            (Value::Number(a), Value::Number(b)) => Value::Number(a + b),
+            (a, b) => Value::String(a.as_string() + &b.as_string()),
+        },
+        "-" => Value::Number(left.as_number()? - right.as_number()?),
+        "*" => Value::Number(left.as_number()? * right.as_number()?),
+        "/" => Value::Number(left.as_number()? / right.as_number()?),
+        "%" => Value::Number(left.as_number()? % right.as_number()?),
+        ">" => Value::Boolean(left.as_number()? > right.as_number()?),
+        "<" => Value::Boolean(left.as_number()? < right.as_number()?),
+        ">=" => Value::Boolean(left.as_number()? >= right.as_number()?),
+        "<=" => Value::Boolean(left.as_number()? <= right.as_number()?),
+        "==" => Value::Boolean(left == right),
+        "!=" => Value::Boolean(left != right),
+        _ => return Err(AdgError::syntax_error(format!("Unknown binary operator '{}'", op))),
+    };
+    Ok(value)
+}
+
+const NATIVE_MODULES: [&str; 5] = ["fb", "geo", "math", "qr", "style"];
+
+fn native_module(name: &str) -> Option<Value> {
+    let functions = match name {
+        "fb" => get_filebox_module(),
+        "geo" => get_geo_module(),
+        "math" => get_math_module(),
+        "qr" => get_qr_module(),
+        "style" => get_style_module(),
+        _ => return None,
+    };
+    Some(module_object(functions))
+}
+
+// Junta `.` e `..` ao caminho sem tocar no disco: `a/./b/../c.adg` vira `a/c.adg`
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(normalized.components().next_back(), Some(Component::Normal(_))) => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+// Módulos nativos viram objetos: `math.sqrt(...)` é um acesso a membro comum
+fn module_object(functions: HashMap<String, Value>) -> Value {
+    let mut entries: Vec<(String, Value)> = functions.into_iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Value::object(entries.into_iter().collect())
+}
+
+/// Sinal de controle de fluxo produzido pela execução de um statement.
+#[derive(Debug, Clone)]
+enum Flow {
+    Normal,
+    Break,
+    Continue,
+    Return(Value),
+}
 
 pub struct Interpreter {
-    globals: Env,
-    locals: Env,
+    // Quadro do escopo em execução; o topo do script é filho do quadro dos nativos
+    env: EnvRef,
+    globals: EnvRef,
+    // Todo código carregado (arquivo principal, módulos, entradas do REPL)
+    sources: SourceMap,
+    // Módulos já avaliados, pelo caminho canônico: cada arquivo roda uma única vez
+    modules: HashMap<PathBuf, Value>,
+    // Arquivos sendo carregados agora (caminho canônico e nome exibido), para detectar ciclos
+    loading: Vec<(PathBuf, String)>,
+    // Nomes exportados pelo módulo em execução
+    exports: Vec<String>,
+    // Pasta de onde partem os imports relativos
+    base_dir: PathBuf,
+    // Avisos já mostrados, com o trecho de cada um: um `match` avisa uma vez só
+    warnings: Vec<(Span, String)>,
+    // Chamadas em andamento, limitadas a `MAX_CALL_DEPTH`
+    depth: usize,
+    // Quadro em que uma declaração pode substituir outra de mesmo nome (o topo do REPL)
+    redeclarable: Option<EnvRef>,
 }
 
 impl Interpreter {
@@ -84,202 +472,2128 @@ impl Interpreter {
             for arg in args {
                 println!("{}", arg.as_string());
             }
-            Value::Null
+            Ok(Value::Null)
         }));
 
-        // Módulo filebox (fb)
-        let filebox = get_filebox_module();
-        for (k, v) in filebox {
-            globals.insert(format!("fb.{}", k), v);
-        }
+        globals.insert("error".to_string(), Value::NativeFunction(|args| {
+            match args.as_slice() {
+                [message] => Ok(Value::Error { kind: "Error".to_string(), message: message.as_string() }),
+                [message, kind] => Ok(Value::Error { kind: kind.as_string(), message: message.as_string() }),
+                _ => Err(AdgError::arity_error("error espera 1 ou 2 argumentos")),
+            }
+        }));
+
+        // Asserções, usadas principalmente pelo `adg test`
+        globals.insert("assert".to_string(), Value::NativeFunction(|args| {
+            let (condition, message) = match args.as_slice() {
+                [condition] => (condition, "Assertion failed".to_string()),
+                [condition, message] => (condition, message.as_string()),
+                _ => return Err(AdgError::arity_error("assert espera 1 ou 2 argumentos")),
+            };
+            if condition.as_bool()? {
+                Ok(Value::Null)
+            } else {
+                Err(assertion_error(message))
+            }
+        }));
+
+        globals.insert("assert_eq".to_string(), Value::NativeFunction(|args| {
+            match args.as_slice() {
+                [actual, expected] if actual == expected => Ok(Value::Null),
+                [actual, expected] => Err(assertion_error(
+                    format!("Expected {}, got {}", expected.as_string(), actual.as_string()),
+                )),
+                _ => Err(AdgError::arity_error("assert_eq espera 2 argumentos")),
+            }
+        }));
+
+        // Argumentos do script (`adg run arquivo.adg -- ...`)
+        globals.insert("args".to_string(), Value::array(vec![]));
 
-        // Módulo math
-        let math = get_math_module();
-        for (k, v) in math {
-            globals.insert(format!("math.{}", k), v);
+        // Módulos nativos: continuam globais, além de importáveis (`import math from "math"`)
+        for name in NATIVE_MODULES {
+            globals.insert(name.to_string(), native_module(name).expect("módulo nativo"));
         }
 
-        // Módulo style
-        let style = get_style_module();
-        for (k, v) in style {
-            globals.insert(format!("style.{}", k), v);
+        let root = Environment::new();
+        for (name, value) in globals {
+            root.borrow_mut().define(name, value);
         }
 
         Interpreter {
-            globals,
-            locals: HashMap::new(),
+            env: Environment::child(&root),
+            globals: root,
+            sources: SourceMap::default(),
+            modules: HashMap::new(),
+            loading: vec![],
+            exports: vec![],
+            base_dir: PathBuf::new(),
+            warnings: vec![],
+            depth: 0,
+            redeclarable: None,
         }
     }
 
-    pub fn execute(&mut self, stmts: Vec<Stmt>) {
-        for stmt in stmts {
-            self.exec_stmt(stmt);
+    /// Executa um arquivo como programa principal: os imports relativos partem da
+    /// pasta dele, e os erros podem ser localizados com `sources`.
+    pub fn run_file(&mut self, path: &str, code: &str) -> Result<(), Vec<AdgError>> {
+        let origin = self.add_source(path, code);
+        let stmts = Parser::new(Lexer::with_origin(code, origin)).parse()?;
+
+        let file = Path::new(path);
+        let dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+        let previous = mem::replace(&mut self.base_dir, dir);
+        // A entrada padrão não tem caminho, então não pode ser importada de volta
+        let canonical = fs::canonicalize(file).ok();
+        if let Some(canonical) = &canonical {
+            self.loading.push((canonical.clone(), path.to_string()));
+        }
+
+        let result = self.execute(stmts);
+
+        if canonical.is_some() {
+            self.loading.pop();
+        }
+        self.base_dir = previous;
+        result.map_err(|err| vec![err])
+    }
+
+    /// Registra código que será analisado com `Lexer::with_origin(code, origem)`.
+    pub fn add_source(&mut self, path: &str, code: &str) -> Span {
+        self.sources.add(path, code)
+    }
+
+    /// Código já carregado, para formatar erros com `AdgError::report_in`.
+    pub fn sources(&self) -> &SourceMap {
+        &self.sources
+    }
+
+    /// Define (ou substitui) um global visível a todo o script, como `args`.
+    pub fn define_global(&mut self, name: &str, value: Value) {
+        self.globals.borrow_mut().define(name.to_string(), value);
+    }
+
+    /// Executa o programa; um erro não capturado interrompe a execução.
+    pub fn execute(&mut self, stmts: Vec<Stmt>) -> Result<(), AdgError> {
+        // O parser já rejeita `break`/`continue` fora de laços
+        match self.exec_body(&stmts)? {
+            Flow::Break => Err(AdgError::syntax_error("'break' outside of a loop")),
+            Flow::Continue => Err(AdgError::syntax_error("'continue' outside of a loop")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Como `execute`, mas devolve o valor quando o último statement é uma expressão
+    /// (usado pelo REPL para mostrar resultados). As declarações no topo substituem
+    /// as de mesmo nome feitas antes, então `let x` e `fn f` podem ser redigitados.
+    pub fn evaluate(&mut self, mut stmts: Vec<Stmt>) -> Result<Option<Value>, AdgError> {
+        let last = match stmts.last() {
+            Some(Stmt { kind: StmtKind::Expression(_), .. }) => stmts.pop(),
+            _ => None,
+        };
+        self.redeclarable = Some(Rc::clone(&self.env));
+        let result = self.execute(stmts);
+        self.redeclarable = None;
+        result?;
+
+        match last {
+            Some(Stmt { kind: StmtKind::Expression(expr), span }) => {
+                self.eval_expr(&expr).map(Some).map_err(|err| err.at(span))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Variáveis declaradas no escopo do script (os nativos ficam de fora).
+    pub fn bindings(&self) -> Vec<(String, Value)> {
+        self.env.borrow().bindings()
+    }
+
+    /// Chama uma função da linguagem (ou nativa) com os argumentos dados.
+    /// Chamar um `struct` cria uma instância.
+    pub fn call(&mut self, callee: Value, args: Vec<Value>) -> Result<Value, AdgError> {
+        match callee {
+            Value::Function { params, body, env } => self.call_function(params, body, env, args),
+            Value::NativeFunction(f) => f(args),
+            Value::Struct(def) => self.construct(def, args),
+            Value::Constructor { def, index } => {
+                let variant = &def.variants[index];
+                if args.len() != variant.fields.len() {
+                    return Err(AdgError::arity_error(format!(
+                        "{}.{} expects {}, got {}",
+                        def.name,
+                        variant.name,
+                        arguments(variant.fields.len()),
+                        args.len()
+                    )));
+                }
+                Ok(Value::Variant { def, index, payload: args })
+            }
+            other => Err(AdgError::type_error(format!("Expected function, got {}", other.type_name()))),
         }
     }
 
-    fn exec_stmt(&mut self, stmt: Stmt) -> Option<Value> {
+    fn exec_stmt(&mut self, stmt: &Stmt) -> Result<Flow, AdgError> {
+        self.exec_stmt_kind(&stmt.kind).map_err(|err| err.at(stmt.span))
+    }
+
+    // Como em `eval_expr_kind`, os statements maiores ficam em métodos próprios
+    fn exec_stmt_kind(&mut self, stmt: &StmtKind) -> Result<Flow, AdgError> {
         match stmt {
-            Stmt::VarDecl { name, value, .. } => {
-                let val = self.eval_expr(value);
-                self.locals.insert(name, val);
-                None
-            }
-            Stmt::Assignment(name, expr) => {
-                let val = self.eval_expr(*expr);
-                self.locals.insert(name, val);
-                None
-            }
-            Stmt::Expression(expr) => {
-                self.eval_expr(expr);
-                None
-            }
-            Stmt::If { condition, then_branch, else_branch } => {
-                if self.eval_expr(condition).as_bool() {
-                    for stmt in then_branch {
-                        self.exec_stmt(stmt);
-                    }
+            StmtKind::VarDecl { name, value, is_const } => {
+                let val = self.eval_expr(value)?;
+                self.declare(name.clone(), val, *is_const)?;
+                Ok(Flow::Normal)
+            }
+            StmtKind::Assignment(name, expr) => {
+                let val = self.eval_expr(expr)?;
+                self.assign(name, val)?;
+                Ok(Flow::Normal)
+            }
+            StmtKind::Expression(expr) => {
+                self.eval_expr(expr)?;
+                Ok(Flow::Normal)
+            }
+            StmtKind::If { condition, then_branch, else_branch } => {
+                if self.eval_expr(condition)?.as_bool()? {
+                    self.exec_block(then_branch)
                 } else if let Some(else_branch) = else_branch {
-                    for stmt in else_branch {
-                        self.exec_stmt(stmt);
-                    }
-                }
-                None
-            }
-            Stmt::Looping { condition, body } => {
-                while self.eval_expr(condition.clone()).as_bool() {
-                    for stmt in &body {
-                        if let Some(Value::String(s)) = self.exec_stmt(stmt.clone()) {
-                            if s == "break" {
-                                return None;
-                            } else if s == "continue" {
-                                break;
-                            }
-                        }
-                    }
-                }
-                None
-            }
-            Stmt::For { init, condition, update, body } => {
-                self.exec_stmt(*init);
-                while self.eval_expr(condition.clone()).as_bool() {
-                    for stmt in &body {
-                        if let Some(Value::String(s)) = self.exec_stmt(stmt.clone()) {
-                            if s == "break" {
-                                return None;
-                            } else if s == "continue" {
-                                break;
-                            }
-                        }
-                    }
-                    self.eval_expr(update.clone());
+                    self.exec_block(else_branch)
+                } else {
+                    Ok(Flow::Normal)
                 }
-                None
             }
-            Stmt::Break => Some(Value::String("break".into())),
-            Stmt::Continue => Some(Value::String("continue".into())),
-            Stmt::Function { name, params, body } => {
+            StmtKind::Looping { condition, body } => self.exec_loop(condition, body, None, true),
+            StmtKind::DoWhile { body, condition } => self.exec_loop(condition, body, None, false),
+            StmtKind::For { init, condition, update, body } => {
+                // A variável do init vive num escopo próprio, que envolve o laço
+                self.in_scope(HashMap::new(), |this| {
+                    this.exec_stmt(init)?;
+                    this.exec_loop(condition, body, Some(update), true)
+                })
+            }
+            StmtKind::Break => Ok(Flow::Break),
+            StmtKind::Continue => Ok(Flow::Continue),
+            StmtKind::Function { name, params, body, .. } => {
                 let func = Value::Function {
-                    params,
-                    body,
-                    env: self.locals.clone(),
+                    params: params.clone(),
+                    body: Rc::clone(body),
+                    env: Rc::clone(&self.env),
                 };
-                self.locals.insert(name, func);
-                None
+                self.declare(name.clone(), func, false)?;
+                Ok(Flow::Normal)
             }
-            Stmt::Return(expr) => {
-                if let Some(e) = expr {
-                    Some(self.eval_expr(e))
-                } else {
-                    Some(Value::Null)
+            StmtKind::Struct { name, fields, methods } => {
+                let def = StructDef {
+                    name: name.clone(),
+                    fields: fields.clone(),
+                    methods: methods.clone(),
+                    env: Rc::clone(&self.env),
+                };
+                self.declare(name.clone(), Value::Struct(Rc::new(def)), false)?;
+                Ok(Flow::Normal)
+            }
+            StmtKind::Enum { name, variants } => {
+                let def = EnumDef { name: name.clone(), variants: variants.clone() };
+                self.declare(name.clone(), Value::Enum(Rc::new(def)), false)?;
+                Ok(Flow::Normal)
+            }
+            StmtKind::Match { subject, arms } => Ok(self.eval_match(subject, arms)?.0),
+            StmtKind::Return(expr) => {
+                let value = match expr {
+                    Some(e) => self.eval_expr(e)?,
+                    None => Value::Null,
+                };
+                Ok(Flow::Return(value))
+            }
+            StmtKind::Throw(expr) => {
+                let value = self.eval_expr(expr)?;
+                Err(AdgError::thrown(value))
+            }
+            StmtKind::Try { body, catch_name, catch_body, finally_body } => {
+                self.exec_try(body, catch_name.as_ref().zip(catch_body.as_ref()), finally_body.as_deref())
+            }
+            StmtKind::Switch { subject, cases } => self.exec_switch(subject, cases),
+            StmtKind::Block(stmts) => self.exec_block(stmts),
+            StmtKind::Import { clause, source } => {
+                self.exec_import(clause, source)?;
+                Ok(Flow::Normal)
+            }
+            StmtKind::Export(decl) => {
+                if let StmtKind::VarDecl { name, .. }
+                | StmtKind::Function { name, .. }
+                | StmtKind::Struct { name, .. }
+                | StmtKind::Enum { name, .. } = &decl.kind
+                {
+                    self.exports.push(name.clone());
+                }
+                self.exec_stmt(decl)
+            }
+            // Só aparece em árvores parciais, que `Parser::parse` nunca devolve
+            StmtKind::Error => Err(AdgError::syntax_error("Cannot execute code with syntax errors")),
+        }
+    }
+
+    // Laço com teste no início; no `do-while` (`check_first` falso), o corpo roda uma
+    // vez antes do primeiro teste. O `update` do `for` roda após cada volta
+    fn exec_loop(
+        &mut self,
+        condition: &Expr,
+        body: &[Stmt],
+        update: Option<&Expr>,
+        check_first: bool,
+    ) -> Result<Flow, AdgError> {
+        let mut check = check_first;
+        loop {
+            if check && !self.eval_expr(condition)?.as_bool()? {
+                break;
+            }
+            check = true;
+            match self.exec_block(body)? {
+                Flow::Break => break,
+                Flow::Normal | Flow::Continue => {}
+                flow @ Flow::Return(_) => return Ok(flow),
+            }
+            if let Some(update) = update {
+                self.eval_expr(update)?;
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn exec_try(
+        &mut self,
+        body: &[Stmt],
+        catch: Option<(&String, &Vec<Stmt>)>,
+        finally_body: Option<&[Stmt]>,
+    ) -> Result<Flow, AdgError> {
+        let mut result = self.exec_block(body);
+
+        if let (Err(err), Some((name, catch_body))) = (&result, catch) {
+            let scope = HashMap::from([(name.clone(), err.value())]);
+            result = self.in_scope(scope, |this| this.exec_body(catch_body));
+        }
+
+        // O finally sempre roda; se ele próprio desviar o fluxo, prevalece
+        if let Some(finally_body) = finally_body {
+            match self.exec_block(finally_body) {
+                Ok(Flow::Normal) => {}
+                other => return other,
+            }
+        }
+
+        result
+    }
+
+    fn exec_switch(&mut self, subject: &Expr, cases: &[SwitchCase]) -> Result<Flow, AdgError> {
+        let subject = self.eval_expr(subject)?;
+
+        // Primeiro case igual (mesma igualdade do `==`); senão, o default
+        let mut start = None;
+        for (i, case) in cases.iter().enumerate() {
+            if let Some(test) = &case.test
+                && self.eval_expr(test)? == subject
+            {
+                start = Some(i);
+                break;
+            }
+        }
+        let start = start.or_else(|| cases.iter().position(|c| c.test.is_none()));
+
+        // A partir do case escolhido, cai nos seguintes até um `break`;
+        // `continue` e `return` seguem para o laço/função externos.
+        // Todos os cases compartilham um único escopo, como no JS.
+        let Some(start) = start else {
+            return Ok(Flow::Normal);
+        };
+        self.in_scope(HashMap::new(), |this| {
+            for case in cases.iter().skip(start) {
+                match this.exec_body(&case.body)? {
+                    Flow::Normal => {}
+                    Flow::Break => break,
+                    flow => return Ok(flow),
                 }
             }
-            Stmt::Block(stmts) => {
-                for stmt in stmts {
-                    self.exec_stmt(stmt);
+            Ok(Flow::Normal)
+        })
+    }
+
+    fn exec_import(&mut self, clause: &ImportClause, source: &str) -> Result<(), AdgError> {
+        let module = self.import(source)?;
+        match clause {
+            // Nomes importados são constantes
+            ImportClause::Module(name) => self.declare(name.clone(), module, true)?,
+            ImportClause::Names(names) => {
+                for (name, local) in names {
+                    let value = match &module {
+                        Value::Object(exports) => exports.borrow().get(name).cloned(),
+                        _ => None,
+                    };
+                    let value = value.ok_or_else(|| AdgError::import_error(
+                        format!("Module '{}' has no export '{}'", source, name),
+                    ))?;
+                    self.declare(local.clone(), value, true)?;
                 }
-                None
             }
         }
+        Ok(())
     }
 
-    fn eval_expr(&mut self, expr: Expr) -> Value {
-        match expr {
-            Expr::Index(array_expr, index_expr) => {
-                let array = self.eval_expr(*array_expr);
-                let index = self.eval_expr(*index_expr).as_number() as usize;
-                match array {
-                    Value::Array(items) => items.get(index).cloned().unwrap_or(Value::Null),
-                    _ => panic!("Expected array for indexing, got {:?}", array),
-                }
-            }
-            Expr::Literal(lit) => match lit {
-                Literal::Number(n) => Value::Number(n),
-                Literal::String(s) => Value::String(s),
-                Literal::Boolean(b) => Value::Boolean(b),
-                Literal::Null => Value::Null,
-                Literal::Array(items) => {
-                    let values = items.into_iter().map(|e| self.eval_expr(e)).collect();
-                    Value::Array(values)
+    // Executa uma sequência de statements, interrompendo no primeiro desvio de fluxo
+    // Módulo nativo pelo nome, ou arquivo por caminho relativo (`./`, `../`) ou absoluto
+    fn import(&mut self, source: &str) -> Result<Value, AdgError> {
+        if !source.starts_with("./") && !source.starts_with("../") && !source.starts_with('/') {
+            return native_module(source).ok_or_else(|| AdgError::import_error(format!(
+                "Unknown module '{}' (files must start with './' or '../')",
+                source,
+            )));
+        }
+
+        let path = normalize(&self.base_dir.join(source));
+        let name = path.display().to_string();
+        let key = fs::canonicalize(&path)
+            .map_err(|err| AdgError::import_error(format!("Cannot find module '{}': {}", name, err)))?;
+
+        if let Some(module) = self.modules.get(&key) {
+            return Ok(module.clone());
+        }
+        if let Some(position) = self.loading.iter().position(|(loading, _)| *loading == key) {
+            let mut cycle: Vec<&str> = self.loading[position..].iter().map(|(_, name)| name.as_str()).collect();
+            cycle.push(&name);
+            return Err(AdgError::import_error(format!("Circular import: {}", cycle.join(" -> "))));
+        }
+
+        let code = fs::read_to_string(&path)
+            .map_err(|err| AdgError::import_error(format!("Cannot read module '{}': {}", name, err)))?;
+        let origin = self.add_source(&name, &code);
+        // Só o primeiro erro de sintaxe do módulo é reportado
+        let stmts = Parser::new(Lexer::with_origin(&code, origin)).parse()
+            .map_err(|mut errors| errors.remove(0))?;
+
+        // Cada módulo roda no próprio escopo, filho apenas dos nativos
+        let frame = Environment::child(&self.globals);
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let previous_dir = mem::replace(&mut self.base_dir, dir);
+        let previous_exports = mem::take(&mut self.exports);
+        self.loading.push((key.clone(), name));
+
+        let result = self.with_env(Rc::clone(&frame), HashMap::new(), |this| this.execute(stmts));
+
+        self.loading.pop();
+        let exports = mem::replace(&mut self.exports, previous_exports);
+        self.base_dir = previous_dir;
+        result?;
+
+        let module = Value::object(exports.into_iter()
+            .filter_map(|name| {
+                let value = frame.borrow().get(&name)?;
+                Some((name, value))
+            })
+            .collect());
+        self.modules.insert(key, module.clone());
+        Ok(module)
+    }
+
+    fn exec_body(&mut self, stmts: &[Stmt]) -> Result<Flow, AdgError> {
+        for stmt in stmts {
+            match self.exec_stmt(stmt)? {
+                Flow::Normal => {}
+                flow => return Ok(flow),
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    // Executa statements num novo escopo léxico
+    fn exec_block(&mut self, stmts: &[Stmt]) -> Result<Flow, AdgError> {
+        self.in_scope(HashMap::new(), |this| this.exec_body(stmts))
+    }
+
+    // Roda `f` num quadro filho do atual, restaurando o anterior mesmo em caso de erro
+    fn in_scope<T>(
+        &mut self,
+        vars: HashMap<String, Value>,
+        f: impl FnOnce(&mut Self) -> Result<T, AdgError>,
+    ) -> Result<T, AdgError> {
+        let frame = Environment::child(&self.env);
+        self.with_env(frame, vars, f)
+    }
+
+    fn with_env<T>(
+        &mut self,
+        frame: EnvRef,
+        vars: HashMap<String, Value>,
+        f: impl FnOnce(&mut Self) -> Result<T, AdgError>,
+    ) -> Result<T, AdgError> {
+        for (name, value) in vars {
+            frame.borrow_mut().define(name, value);
+        }
+        let previous = std::mem::replace(&mut self.env, frame);
+        let result = f(self);
+        self.env = previous;
+        result
+    }
+
+    fn declare(&mut self, name: String, value: Value, is_const: bool) -> Result<(), AdgError> {
+        if self.redeclarable.as_ref().is_some_and(|frame| Rc::ptr_eq(frame, &self.env)) {
+            self.env.borrow_mut().redeclare(name, value, is_const);
+            return Ok(());
+        }
+        self.env.borrow_mut().declare(name, value, is_const)
+    }
+
+    fn assign(&mut self, name: &str, value: Value) -> Result<(), AdgError> {
+        self.env.borrow_mut().assign(name, value)
+    }
+
+    pub fn lookup(&self, name: &str) -> Option<Value> {
+        self.env.borrow().get(name)
+    }
+
+    // Conta uma chamada aninhada enquanto `f` roda; recursão sem fim vira um erro
+    // capturável em vez de estourar a pilha nativa
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, AdgError>) -> Result<T, AdgError> {
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(AdgError::recursion_error("Maximum call depth exceeded"));
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn call_function(
+        &mut self,
+        params: Vec<String>,
+        body: Rc<[Stmt]>,
+        env: EnvRef,
+        args: Vec<Value>,
+    ) -> Result<Value, AdgError> {
+        // O quadro da chamada é filho do quadro onde a função foi definida; parâmetros
+        // sem argumento valem null
+        let frame = Environment::child(&env);
+        let vars = params.into_iter().zip(args.into_iter().chain(std::iter::repeat(Value::Null))).collect();
+        let flow = self.nested(|this| this.with_env(frame, vars, |this| this.exec_body(&body)))?;
+        match flow {
+            Flow::Return(v) => Ok(v),
+            Flow::Normal => Ok(Value::Null),
+            Flow::Break => Err(AdgError::syntax_error("'break' outside of a loop")),
+            Flow::Continue => Err(AdgError::syntax_error("'continue' outside of a loop")),
+        }
+    }
+
+    fn construct(&mut self, def: Rc<StructDef>, args: Vec<Value>) -> Result<Value, AdgError> {
+        self.nested(|this| this.construct_instance(def, args))
+    }
+
+    // Sem `init`, os argumentos preenchem os campos na ordem da declaração; com ele,
+    // os campos começam com os valores padrão e o `init` recebe os argumentos
+    fn construct_instance(&mut self, def: Rc<StructDef>, args: Vec<Value>) -> Result<Value, AdgError> {
+        let init = def.method("init");
+        let max = init.map_or(def.fields.len(), |init| init.params.len());
+        if args.len() > max {
+            return Err(AdgError::arity_error(format!(
+                "{} expects at most {}, got {}",
+                def.name,
+                arguments(max),
+                args.len()
+            )));
+        }
+
+        let (positional, init_args) = if init.is_some() { (vec![], args) } else { (args, vec![]) };
+        let mut positional = positional.into_iter();
+        let mut fields = IndexMap::new();
+        for field in &def.fields {
+            let value = match (positional.next(), &field.default) {
+                (Some(value), _) => value,
+                // O padrão é avaliado a cada instância, para não compartilhar arrays e objetos
+                (None, Some(default)) => {
+                    let frame = Environment::child(&def.env);
+                    self.with_env(frame, HashMap::new(), |this| this.eval_expr(default))?
                 }
-            },
-            Expr::Variable(name) => {
-                self.locals.get(&name)
-                    .or_else(|| self.globals.get(&name))
-                    .cloned()
-                    .unwrap_or_else(|| panic!("Undefined variable '{}'", name))
-            }
-            Expr::Assignment(name, expr) => {
-                let value = self.eval_expr(*expr);
-                self.locals.insert(name.clone(), value.clone());
-                value
-            }
-            Expr::BinaryOp(left, op, right) => {
-                let left = self.eval_expr(*left);
-                let right = self.eval_expr(*right);
-                match op.as_str() {
-                    "+" => match (left, right) {
-                        (Value::Number(a), Value::Number(b)) => Value::Number(a + b),
-                        (a, b) => Value::String(a.as_string() + &b.as_string()),
-                    },
-                    "-" => Value::Number(left.as_number() - right.as_number()),
-                    "*" => Value::Number(left.as_number() * right.as_number()),
-                    "/" => Value::Number(left.as_number() / right.as_number()),
-                    ">" => Value::Boolean(left.as_number() > right.as_number()),
-                    "<" => Value::Boolean(left.as_number() < right.as_number()),
-                    ">=" => Value::Boolean(left.as_number() >= right.as_number()),
-                    "<=" => Value::Boolean(left.as_number() <= right.as_number()),
-                    "==" => Value::Boolean(left == right),
-                    "!=" => Value::Boolean(left != right),
-                    _ => panic!("Unknown binary operator '{}'", op),
-                }
-            }
-            Expr::Call(name, args) => {
-                let args = args.into_iter().map(|e| self.eval_expr(e)).collect::<Vec<_>>();
-                if let Some(val) = self.locals.get(&name).or_else(|| self.globals.get(&name)) {
-                    match val {
-                        Value::Function { params, body, env } => {
-                            let mut local = env.clone();
-                            for (param, arg) in params.iter().zip(args) {
-                                local.insert(param.clone(), arg);
-                            }
-                            let mut sub = Interpreter {
-                                globals: self.globals.clone(),
-                                locals: local,
-                            };
-                            for stmt in body.clone() {
-                                if let Some(v) = sub.exec_stmt(stmt) {
-                                    return v;
-                                }
-                            }
-                            Value::Null
+                (None, None) => Value::Null,
+            };
+            fields.insert(field.name.clone(), value);
+        }
+
+        let instance = Value::Instance(Rc::new(Instance {
+            def: Rc::clone(&def),
+            fields: Rc::new(RefCell::new(fields)),
+        }));
+        if let Some(init) = init {
+            self.call(bind_method(&instance, &def, init), init_args)?;
+        }
+        Ok(instance)
+    }
+
+    // Roda o primeiro braço cujo padrão casa e cuja guarda é verdadeira, num escopo
+    // com as variáveis do padrão. O valor é o da última expressão do corpo (ou null)
+    fn eval_match(&mut self, subject: &Expr, arms: &[MatchArm]) -> Result<(Flow, Value), AdgError> {
+        let span = subject.span;
+        let value = self.eval_expr(subject)?;
+
+        if let Value::Variant { def, .. } = &value
+            && !self.warnings.iter().any(|(warned, _)| *warned == span)
+        {
+            let missing = self.uncovered_variants(def, arms);
+            if !missing.is_empty() {
+                self.warn(span, format!("Non-exhaustive match on {}: {} not covered", def.name, missing.join(", ")));
+            }
+        }
+
+        for arm in arms {
+            let mut bindings = HashMap::new();
+            if !self.match_pattern(&arm.pattern, &value, &mut bindings)? {
+                continue;
+            }
+            let result = self.in_scope(bindings, |this| {
+                if let Some(guard) = &arm.guard
+                    && !this.eval_expr(guard)?.as_bool()?
+                {
+                    return Ok(None);
+                }
+                this.exec_arm(&arm.body).map(Some)
+            })?;
+            if let Some(result) = result {
+                return Ok(result);
+            }
+        }
+        Err(AdgError::match_error(format!("No match arm for {}", value.as_string())).at(span))
+    }
+
+    fn exec_arm(&mut self, body: &[Stmt]) -> Result<(Flow, Value), AdgError> {
+        let (body, last) = match body.split_last() {
+            Some((last @ Stmt { kind: StmtKind::Expression(_), .. }, rest)) => (rest, Some(last)),
+            _ => (body, None),
+        };
+        match (self.exec_body(body)?, last) {
+            (Flow::Normal, Some(Stmt { kind: StmtKind::Expression(expr), .. })) => {
+                Ok((Flow::Normal, self.eval_expr(expr)?))
+            }
+            (flow, _) => Ok((flow, Value::Null)),
+        }
+    }
+
+    fn match_pattern(
+        &self,
+        pattern: &Pattern,
+        value: &Value,
+        bindings: &mut HashMap<String, Value>,
+    ) -> Result<bool, AdgError> {
+        match pattern {
+            Pattern::Wildcard => Ok(true),
+            Pattern::Binding(name) => {
+                bindings.insert(name.clone(), value.clone());
+                Ok(true)
+            }
+            Pattern::Literal(literal) => Ok(match (literal, value) {
+                (Literal::Number(a), Value::Number(b)) => a == b,
+                (Literal::String(a), Value::String(b)) => a == b,
+                (Literal::Boolean(a), Value::Boolean(b)) => a == b,
+                (Literal::Null, Value::Null) => true,
+                _ => false,
+            }),
+            Pattern::Variant { enum_name, variant, args } => {
+                let (def, index) = self.resolve_variant(enum_name, variant)?;
+                let fields = def.variants[index].fields.len();
+                if let Some(args) = args
+                    && args.len() != fields
+                {
+                    return Err(AdgError::type_error(format!(
+                        "Pattern {}.{} expects {} fields, got {}",
+                        def.name,
+                        variant,
+                        fields,
+                        args.len()
+                    )));
+                }
+                match value {
+                    Value::Variant { def: actual, index: actual_index, payload }
+                        if Rc::ptr_eq(&def, actual) && index == *actual_index =>
+                    {
+                        match args {
+                            Some(args) => self.match_all(args.iter().zip(payload), bindings),
+                            None => Ok(true),
                         }
-                        Value::NativeFunction(f) => f(args),
-                        _ => panic!("'{}' is not a function", name),
                     }
-                } else {
-                    panic!("Function '{}' not found", name);
+                    _ => Ok(false),
+                }
+            }
+            Pattern::Array { items, rest } => {
+                let Value::Array(array) = value else { return Ok(false) };
+                let array = array.borrow();
+                let fits = if rest.is_some() { array.len() >= items.len() } else { array.len() == items.len() };
+                if !fits || !self.match_all(items.iter().zip(array.iter()), bindings)? {
+                    return Ok(false);
+                }
+                if let Some(rest) = rest.as_ref().filter(|rest| *rest != "_") {
+                    bindings.insert(rest.clone(), Value::array(array[items.len()..].to_vec()));
+                }
+                Ok(true)
+            }
+            // Casa com objetos e instâncias que tenham todas as chaves do padrão
+            Pattern::Object(entries) => {
+                let fields = match value {
+                    Value::Object(obj) => obj,
+                    Value::Instance(instance) => &instance.fields,
+                    _ => return Ok(false),
+                };
+                let fields = fields.borrow();
+                let mut pairs = vec![];
+                for (key, pattern) in entries {
+                    match fields.get(key) {
+                        Some(value) => pairs.push((pattern, value)),
+                        None => return Ok(false),
+                    }
                 }
+                self.match_all(pairs.into_iter(), bindings)
             }
         }
     }
+
+    fn match_all<'a>(
+        &self,
+        pairs: impl Iterator<Item = (&'a Pattern, &'a Value)>,
+        bindings: &mut HashMap<String, Value>,
+    ) -> Result<bool, AdgError> {
+        for (pattern, value) in pairs {
+            if !self.match_pattern(pattern, value, bindings)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    // Variante citada num padrão (`Shape.Circle`), com o enum procurado pelo nome
+    fn resolve_variant(&self, enum_name: &str, variant: &str) -> Result<(Rc<EnumDef>, usize), AdgError> {
+        match self.lookup(enum_name) {
+            Some(Value::Enum(def)) => match def.variant(variant) {
+                Some(index) => Ok((def, index)),
+                None => Err(AdgError::type_error(format!("{} has no variant '{}'", def.name, variant))),
+            },
+            Some(other) => Err(AdgError::type_error(
+                format!("'{}' is not an enum, got {}", enum_name, other.type_name()),
+            )),
+            None => Err(AdgError::name_error(format!("Undefined variable '{}'", enum_name))),
+        }
+    }
+
+    // Variantes do enum que nenhum braço sem guarda cobre por inteiro
+    fn uncovered_variants(&self, def: &Rc<EnumDef>, arms: &[MatchArm]) -> Vec<String> {
+        let mut covered = vec![false; def.variants.len()];
+        for arm in arms.iter().filter(|arm| arm.guard.is_none()) {
+            match &arm.pattern {
+                pattern if pattern.is_irrefutable() => return vec![],
+                Pattern::Variant { enum_name, variant, args } => {
+                    if let Ok((target, index)) = self.resolve_variant(enum_name, variant)
+                        && Rc::ptr_eq(&target, def)
+                        && args.iter().flatten().all(Pattern::is_irrefutable)
+                    {
+                        covered[index] = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+        def.variants.iter()
+            .zip(covered)
+            .filter(|(_, covered)| !covered)
+            .map(|(variant, _)| format!("{}.{}", def.name, variant.name))
+            .collect()
+    }
+
+    // Avisos vão para o stderr, sem interromper a execução
+    fn warn(&mut self, span: Span, message: String) {
+        match self.sources.locate(span) {
+            Some((path, source, local)) => eprintln!("{}", render_as("aviso", path, source, local, &message)),
+            None => eprintln!("aviso: {}", message),
+        }
+        self.warnings.push((span, message));
+    }
+
+    fn resolve_place(&mut self, target: &Expr) -> Result<Place, AdgError> {
+        self.resolve_place_kind(&target.kind).map_err(|err| err.at(target.span))
+    }
+
+    fn resolve_place_kind(&mut self, target: &ExprKind) -> Result<Place, AdgError> {
+        match target {
+            ExprKind::Variable(name) => Ok(Place::Variable(name.clone())),
+            ExprKind::Member(object, name) => match self.eval_expr(object)? {
+                Value::Object(obj) => self.property_place(obj, name.clone()),
+                Value::Instance(instance) => instance.field_place(name.clone()),
+                other => Err(AdgError::type_error(
+                    format!("Cannot set property '{}' of {}", name, other.type_name()),
+                )),
+            },
+            ExprKind::Index(target, index) => {
+                let target = self.eval_expr(target)?;
+                let index = self.eval_expr(index)?;
+                match (target, index) {
+                    (Value::Array(items), index) => Ok(Place::Element(items, array_index(&index)?)),
+                    (Value::Object(obj), Value::String(key)) => self.property_place(obj, key),
+                    (Value::Instance(instance), Value::String(key)) => instance.field_place(key),
+                    (Value::Object(_) | Value::Instance(_), other) => Err(AdgError::type_error(
+                        format!("Object keys must be strings, got {}", other.type_name()),
+                    )),
+                    (other, _) => Err(AdgError::type_error(
+                        format!("Expected array or object for indexing, got {}", other.type_name()),
+                    )),
+                }
+            }
+            _ => Err(AdgError::syntax_error("Invalid assignment target")),
+        }
+    }
+
+    // O objeto de exports de um módulo é o mesmo para todos que o importam, então
+    // não aceita atribuição
+    fn property_place(&self, obj: ObjectRef, name: String) -> Result<Place, AdgError> {
+        let exports = self.modules.values()
+            .any(|module| matches!(module, Value::Object(exports) if Rc::ptr_eq(exports, &obj)));
+        if exports {
+            return Err(AdgError::type_error(format!("Cannot assign to '{}': module exports are read-only", name)));
+        }
+        Ok(Place::Property(obj, name))
+    }
+
+    fn read_place(&self, place: &Place) -> Result<Value, AdgError> {
+        match place {
+            Place::Variable(name) => self.lookup(name)
+                .ok_or_else(|| AdgError::name_error(format!("Undefined variable '{}'", name))),
+            Place::Property(obj, key) => Ok(obj.borrow().get(key).cloned().unwrap_or(Value::Null)),
+            Place::Element(items, index) => Ok(items.borrow().get(*index).cloned().unwrap_or(Value::Null)),
+        }
+    }
+
+    fn write_place(&mut self, place: Place, value: Value) -> Result<(), AdgError> {
+        match place {
+            Place::Variable(name) => self.assign(&name, value),
+            Place::Property(obj, key) => {
+                obj.borrow_mut().insert(key, value);
+                Ok(())
+            }
+            Place::Element(items, index) => {
+                // Escrever além do fim estende o array, preenchendo o intervalo com null
+                let mut items = items.borrow_mut();
+                if index >= items.len() {
+                    items.resize(index + 1, Value::Null);
+                }
+                items[index] = value;
+                Ok(())
+            }
+        }
+    }
+
+    fn eval_expr(&mut self, expr: &Expr) -> Result<Value, AdgError> {
+        self.eval_expr_kind(&expr.kind).map_err(|err| err.at(expr.span))
+    }
+
+    // Cada forma de expressão fica num método próprio: no build de debug, o quadro de um
+    // `match` único somaria os temporários de todos os braços a cada nível de recursão
+    fn eval_expr_kind(&mut self, expr: &ExprKind) -> Result<Value, AdgError> {
+        match expr {
+            ExprKind::Index(target, index) => self.eval_index(target, index),
+            ExprKind::Member(object, name) => self.eval_expr(object)?.get_member(name),
+            ExprKind::Template(parts) => self.eval_template(parts),
+            ExprKind::Literal(lit) => self.eval_literal(lit),
+            ExprKind::Variable(name) => {
+                self.lookup(name)
+                    .ok_or_else(|| AdgError::name_error(format!("Undefined variable '{}'", name)))
+            }
+            ExprKind::Assignment(target, expr) => self.eval_assignment(target, None, expr),
+            ExprKind::CompoundAssignment(target, op, expr) => self.eval_assignment(target, Some(op), expr),
+            ExprKind::Update { target, op, prefix } => self.eval_update(target, op, *prefix),
+            ExprKind::Unary(op, operand) => self.eval_unary(op, operand),
+            ExprKind::BinaryOp(left, op, right) => self.eval_binary(left, op, right),
+            ExprKind::Call(callee, args) => self.eval_call(callee, args),
+            ExprKind::Function { params, body } => Ok(Value::Function {
+                params: params.clone(),
+                body: Rc::clone(body),
+                env: Rc::clone(&self.env),
+            }),
+            ExprKind::Match { subject, arms } => match self.eval_match(subject, arms)? {
+                (Flow::Normal, value) => Ok(value),
+                // O parser já rejeita `return`, `break` e `continue` nesses braços
+                _ => Err(AdgError::syntax_error("Cannot leave a match expression with return, break or continue")),
+            },
+            ExprKind::Error => Err(AdgError::syntax_error("Cannot execute code with syntax errors")),
+        }
+    }
+
+    fn eval_index(&mut self, target: &Expr, index: &Expr) -> Result<Value, AdgError> {
+        let target = self.eval_expr(target)?;
+        let index = self.eval_expr(index)?;
+        match target {
+            // Leitura fora dos limites vale null
+            Value::Array(items) => Ok(items.borrow().get(array_index(&index)?).cloned().unwrap_or(Value::Null)),
+            Value::Object(_) | Value::Instance(_) => match index {
+                Value::String(key) => target.get_member(&key),
+                other => Err(AdgError::type_error(
+                    format!("Object keys must be strings, got {}", other.type_name()),
+                )),
+            },
+            _ => Err(AdgError::type_error(
+                format!("Expected array or object for indexing, got {}", target.type_name()),
+            )),
+        }
+    }
+
+    fn eval_template(&mut self, parts: &[Expr]) -> Result<Value, AdgError> {
+        let mut text = String::new();
+        for part in parts {
+            text.push_str(&self.eval_expr(part)?.as_string());
+        }
+        Ok(Value::String(text))
+    }
+
+    fn eval_literal(&mut self, lit: &Literal) -> Result<Value, AdgError> {
+        match lit {
+            Literal::Number(n) => Ok(Value::Number(*n)),
+            Literal::String(s) => Ok(Value::String(s.clone())),
+            Literal::Boolean(b) => Ok(Value::Boolean(*b)),
+            Literal::Null => Ok(Value::Null),
+            Literal::Array(items) => {
+                let values = items.iter()
+                    .map(|e| self.eval_expr(e))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Value::array(values))
+            }
+            Literal::Object(entries) => {
+                let mut obj = IndexMap::new();
+                for (key, expr) in entries {
+                    let value = self.eval_expr(expr)?;
+                    obj.insert(key.clone(), value);
+                }
+                Ok(Value::object(obj))
+            }
+        }
+    }
+
+    // `alvo = valor`, ou `alvo op= valor` quando há um operador
+    fn eval_assignment(&mut self, target: &Expr, op: Option<&String>, expr: &Expr) -> Result<Value, AdgError> {
+        let place = self.resolve_place(target)?;
+        let value = match op {
+            Some(op) => {
+                let current = self.read_place(&place)?;
+                binary_op(op, current, self.eval_expr(expr)?)?
+            }
+            None => self.eval_expr(expr)?,
+        };
+        self.write_place(place, value.clone())?;
+        Ok(value)
+    }
+
+    fn eval_update(&mut self, target: &Expr, op: &str, prefix: bool) -> Result<Value, AdgError> {
+        let place = self.resolve_place(target)?;
+        let old = self.read_place(&place)?.as_number()?;
+        let new = if op == "++" { old + 1.0 } else { old - 1.0 };
+        self.write_place(place, Value::Number(new))?;
+        Ok(Value::Number(if prefix { new } else { old }))
+    }
+
+    fn eval_unary(&mut self, op: &str, operand: &Expr) -> Result<Value, AdgError> {
+        let value = self.eval_expr(operand)?;
+        match op {
+            "-" => Ok(Value::Number(-value.as_number()?)),
+            "!" => Ok(Value::Boolean(!value.as_bool()?)),
+            _ => Err(AdgError::syntax_error(format!("Unknown unary operator '{}'", op))),
+        }
+    }
+
+    fn eval_binary(&mut self, left: &Expr, op: &str, right: &Expr) -> Result<Value, AdgError> {
+        if op == "&&" || op == "||" {
+            // Curto-circuito: o lado direito só é avaliado se for decisivo
+            let left = self.eval_expr(left)?.as_bool()?;
+            if (op == "&&") != left {
+                return Ok(Value::Boolean(left));
+            }
+            return Ok(Value::Boolean(self.eval_expr(right)?.as_bool()?));
+        }
+        let left = self.eval_expr(left)?;
+        let right = self.eval_expr(right)?;
+        binary_op(op, left, right)
+    }
+
+    fn eval_call(&mut self, callee: &Expr, args: &[Expr]) -> Result<Value, AdgError> {
+        let name = match &callee.kind {
+            ExprKind::Variable(name) => format!("'{}'", name),
+            ExprKind::Member(_, name) => format!("'{}'", name),
+            _ => "callee".to_string(),
+        };
+        let callee = self.eval_expr(callee)?;
+        let args = args.iter()
+            .map(|e| self.eval_expr(e))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Argumentos a mais são um erro; os que faltam viram null
+        if let Value::Function { params, .. } = &callee
+            && args.len() > params.len()
+        {
+            return Err(AdgError::arity_error(format!(
+                "{} expects at most {}, got {}",
+                name,
+                arguments(params.len()),
+                args.len()
+            )));
+        }
+        match callee {
+            Value::Function { .. }
+            | Value::NativeFunction(_)
+            | Value::Struct(_)
+            | Value::Constructor { .. } => self.call(callee, args),
+            other => Err(AdgError::type_error(
+                format!("{} is not a function, got {}", name, other.type_name()),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn run(source: &str) -> Interpreter {
+        let mut interpreter = Interpreter::new();
+        if let Err(err) = interpreter.execute(parse(source)) {
+            panic!("Uncaught {}", err.value().as_string());
+        }
+        interpreter
+    }
+
+    fn parse(source: &str) -> Vec<Stmt> {
+        Parser::new(Lexer::new(source)).parse()
+            .unwrap_or_else(|errors| panic!("{}", errors[0]))
+    }
+
+    fn get(interpreter: &Interpreter, name: &str) -> Value {
+        interpreter.env.borrow().get(name)
+            .unwrap_or_else(|| panic!("'{}' not defined", name))
+    }
+
+    #[test]
+    fn logical_operators_short_circuit() {
+        let interpreter = run(r#"
+            let a = false && missing;
+            let b = true || missing;
+            let c = true && 1 < 2;
+            let d = false || 2 < 1;
+        "#);
+
+        assert_eq!(get(&interpreter, "a"), Value::Boolean(false));
+        assert_eq!(get(&interpreter, "b"), Value::Boolean(true));
+        assert_eq!(get(&interpreter, "c"), Value::Boolean(true));
+        assert_eq!(get(&interpreter, "d"), Value::Boolean(false));
+    }
+
+    #[test]
+    fn modulo_follows_dividend_sign() {
+        let interpreter = run("let a = 7 % 3; let b = -7 % 3; let c = 5.5 % 2;");
+
+        assert_eq!(get(&interpreter, "a"), Value::Number(1.0));
+        assert_eq!(get(&interpreter, "b"), Value::Number(-1.0));
+        assert_eq!(get(&interpreter, "c"), Value::Number(1.5));
+    }
+
+    #[test]
+    fn null_is_a_literal() {
+        let interpreter = run(r#"
+            let x = null;
+            assert_eq(x, null);
+            let missing = {}.nada == null;
+        "#);
+
+        assert_eq!(get(&interpreter, "x"), Value::Null);
+        assert_eq!(get(&interpreter, "missing"), Value::Boolean(true));
+    }
+
+    #[test]
+    fn try_catch_captures_runtime_and_thrown_errors() {
+        let interpreter = run(r#"
+            let kind = "";
+            let message = "";
+            try {
+                let x = missing + 1;
+            } catch (e) {
+                kind = e.kind;
+                message = e.message;
+            }
+
+            let thrown = "";
+            try {
+                throw "boom";
+            } catch (e) {
+                thrown = e;
+            }
+
+            let custom = "";
+            fn fail() { throw error("sem estoque", "StockError"); }
+            try { fail(); } catch (e) { custom = e.kind + "/" + e.message; }
+        "#);
+
+        assert_eq!(get(&interpreter, "kind"), Value::String("NameError".into()));
+        assert_eq!(get(&interpreter, "message"), Value::String("Undefined variable 'missing'".into()));
+        assert_eq!(get(&interpreter, "thrown"), Value::String("boom".into()));
+        assert_eq!(get(&interpreter, "custom"), Value::String("StockError/sem estoque".into()));
+    }
+
+    #[test]
+    fn finally_runs_after_try_and_catch() {
+        let interpreter = run(r#"
+            let steps = "";
+            try {
+                steps = steps + "t";
+                throw 1;
+            } catch (e) {
+                steps = steps + "c";
+            } finally {
+                steps = steps + "f";
+            }
+
+            let io = "";
+            try { fb.readFile("/caminho/que/nao/existe.txt"); } catch (e) { io = e.kind; }
+        "#);
+
+        assert_eq!(get(&interpreter, "steps"), Value::String("tcf".into()));
+        assert_eq!(get(&interpreter, "io"), Value::String("IOError".into()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Uncaught TypeError: Expected number, got string")]
+    fn uncaught_errors_abort_execution() {
+        run(r#"let x = "a" - 1;"#);
+    }
+
+    #[test]
+    fn errors_are_structured_by_kind() {
+        let cases = [
+            ("missing;", "NameError: Undefined variable 'missing'"),
+            ("1 - true;", "TypeError: Expected number, got boolean"),
+            ("[1][-1];", "IndexError: Invalid array index -1"),
+            ("math.sqrt();", "ArityError: math.sqrt espera 1 argumento"),
+            ("throw 42;", "Uncaught 42"),
+            (r#"throw error("sem estoque", "StockError");"#, "StockError: sem estoque"),
+        ];
+
+        for (source, expected) in cases {
+            let err = Interpreter::new().execute(parse(source)).expect_err(source);
+            assert_eq!(err.to_string(), expected, "source: {}", source);
+        }
+
+        let err = Interpreter::new().execute(parse(r#"fb.readFile("/nao/existe.adg");"#)).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Io(_)), "{:?}", err);
+        let err = Interpreter::new().execute(parse("throw [1];")).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Thrown(Value::Array(_))), "{:?}", err);
+
+        let errors = Parser::new(Lexer::new("let x = 0b2;")).parse().unwrap_err();
+        assert!(matches!(errors[0].kind, ErrorKind::Lex(_)), "{:?}", errors);
+        let errors = Parser::new(Lexer::new("let = 2;")).parse().unwrap_err();
+        assert!(matches!(errors[0].kind, ErrorKind::Parse(_)), "{:?}", errors);
+    }
+
+    #[test]
+    fn runtime_errors_point_at_the_innermost_expression() {
+        let cases = [
+            (r#"let x = 1 + ("a" - 1);"#, r#"("a" - 1)"#),
+            ("let y = 2;\nprint(y + missing);", "missing"),
+            ("fn f(o) {\n  return o.x.y;\n}\nf({ x: 1 });", "o.x.y"),
+            ("let items = [1];\nitems[-1] = 0;", "items[-1]"),
+            ("const c = 1;\nc += 1;", "c += 1"),
+            ("let v = 1;\nlet v = 2;", "let v = 2;"),
+            ("throw error(\"x\");", "throw error(\"x\");"),
+            ("math.sqrt();", "math.sqrt()"),
+        ];
+
+        for (source, expected) in cases {
+            let err = Interpreter::new().execute(parse(source)).expect_err(source);
+            let span = err.span.unwrap_or_else(|| panic!("no span for {}", source));
+            assert_eq!(&source[span.start..span.end], expected, "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn switch_matches_falls_through_and_defaults() {
+        let interpreter = run(r#"
+            fn menu(op) {
+                let out = "";
+                switch (op) {
+                    case 1:
+                        out = out + "um";
+                    case 2:
+                        out = out + "dois";
+                        break;
+                    case "sair":
+                        out = "tchau";
+                        break;
+                    default:
+                        out = "?";
+                }
+                return out;
+            }
+
+            let a = menu(1);
+            let b = menu(2);
+            let c = menu("sair");
+            let d = menu(42);
+            let e = menu("1");
+        "#);
+
+        assert_eq!(get(&interpreter, "a"), Value::String("umdois".into()));
+        assert_eq!(get(&interpreter, "b"), Value::String("dois".into()));
+        assert_eq!(get(&interpreter, "c"), Value::String("tchau".into()));
+        assert_eq!(get(&interpreter, "d"), Value::String("?".into()));
+        assert_eq!(get(&interpreter, "e"), Value::String("?".into()));
+    }
+
+    #[test]
+    fn switch_without_match_or_default_does_nothing() {
+        let interpreter = run(r#"
+            let hit = false;
+            switch (3) {
+                case 1: hit = true;
+                case 2: hit = true;
+            }
+        "#);
+
+        assert_eq!(get(&interpreter, "hit"), Value::Boolean(false));
+    }
+
+    #[test]
+    fn do_while_runs_body_before_checking_condition() {
+        let interpreter = run(r#"
+            let runs = 0;
+            do {
+                runs = runs + 1;
+            } while (false);
+
+            let n = 0;
+            while (n < 5) {
+                n = n + 1;
+            }
+        "#);
+
+        assert_eq!(get(&interpreter, "runs"), Value::Number(1.0));
+        assert_eq!(get(&interpreter, "n"), Value::Number(5.0));
+    }
+
+    #[test]
+    fn return_propagates_out_of_nested_blocks() {
+        let interpreter = run(r#"
+            fn in_if_in_loop() {
+                let i = 0;
+                looping (true) {
+                    if (i == 3) { return i; }
+                    i = i + 1;
+                }
+                return -1;
+            }
+
+            fn in_block() {
+                { { return "block"; } }
+                return "after";
+            }
+
+            fn in_for_else() {
+                for (let i = 0; i < 10; i = i + 1) {
+                    if (i < 2) { continue; } else { return i * 10; }
+                }
+                return -1;
+            }
+
+            fn in_switch_in_loop() {
+                let i = 0;
+                while (true) {
+                    switch (i) {
+                        case 2: return "dois";
+                        default: i = i + 1;
+                    }
+                }
+            }
+
+            fn in_try_with_finally() {
+                let n = 0;
+                try { return "try"; } finally { n = 1; }
+            }
+
+            fn in_do_while() {
+                do { if (true) { return "do"; } } while (true);
+            }
+
+            let a = in_if_in_loop();
+            let b = in_block();
+            let c = in_for_else();
+            let d = in_switch_in_loop();
+            let e = in_try_with_finally();
+            let f = in_do_while();
+        "#);
+
+        assert_eq!(get(&interpreter, "a"), Value::Number(3.0));
+        assert_eq!(get(&interpreter, "b"), Value::String("block".into()));
+        assert_eq!(get(&interpreter, "c"), Value::Number(20.0));
+        assert_eq!(get(&interpreter, "d"), Value::String("dois".into()));
+        assert_eq!(get(&interpreter, "e"), Value::String("try".into()));
+        assert_eq!(get(&interpreter, "f"), Value::String("do".into()));
+    }
+
+    #[test]
+    fn break_and_continue_through_nested_blocks() {
+        let interpreter = run(r#"
+            let i = 0;
+            looping (true) {
+                i = i + 1;
+                if (i >= 4) { { break; } }
+            }
+
+            let evens = 0;
+            for (let j = 0; j < 10; j = j + 1) {
+                if (j % 2 == 1) { continue; }
+                evens = evens + 1;
+            }
+
+            let k = 0;
+            let skipped = 0;
+            do {
+                k = k + 1;
+                { if (k < 3) { skipped = skipped + 1; continue; } }
+            } while (k < 5);
+
+            let pairs = 0;
+            for (let x = 0; x < 3; x = x + 1) {
+                for (let y = 0; y < 3; y = y + 1) {
+                    if (y > x) { break; }
+                    pairs = pairs + 1;
+                }
+            }
+
+            let seen = 0;
+            for (let n = 0; n < 5; n = n + 1) {
+                switch (n) {
+                    case 1: continue;
+                    case 3: break;
+                }
+                seen = seen + 1;
+            }
+        "#);
+
+        assert_eq!(get(&interpreter, "i"), Value::Number(4.0));
+        assert_eq!(get(&interpreter, "evens"), Value::Number(5.0));
+        assert_eq!(get(&interpreter, "skipped"), Value::Number(2.0));
+        assert_eq!(get(&interpreter, "pairs"), Value::Number(6.0));
+        assert_eq!(get(&interpreter, "seen"), Value::Number(4.0));
+    }
+
+    #[test]
+    fn strings_named_like_signals_are_plain_values() {
+        let interpreter = run(r#"
+            fn signal() { return "break"; }
+            let count = 0;
+            looping (count < 3) {
+                signal();
+                count = count + 1;
+            }
+            let s = signal();
+        "#);
+
+        assert_eq!(get(&interpreter, "count"), Value::Number(3.0));
+        assert_eq!(get(&interpreter, "s"), Value::String("break".into()));
+    }
+
+    #[test]
+    #[should_panic(expected = "'break' outside of a loop")]
+    fn break_outside_loop_is_an_error() {
+        run("fn f() { break; } f();");
+    }
+
+    #[test]
+    fn block_scoping_and_shadowing() {
+        let interpreter = run(r#"
+            let x = "fora";
+            let seen = "";
+            {
+                let x = "dentro";
+                seen = x;
+            }
+            if (true) {
+                let y = 1;
+            }
+            for (let i = 0; i < 2; i = i + 1) {
+                let tmp = i;
+            }
+        "#);
+
+        assert_eq!(get(&interpreter, "x"), Value::String("fora".into()));
+        assert_eq!(get(&interpreter, "seen"), Value::String("dentro".into()));
+        assert!(!interpreter.env.borrow().contains("y"));
+        assert!(!interpreter.env.borrow().contains("i"));
+        assert!(!interpreter.env.borrow().contains("tmp"));
+    }
+
+    #[test]
+    fn declaration_and_assignment_errors() {
+        let interpreter = run(r#"
+            const LIMITE = 10;
+            let a = "";
+            let b = "";
+            let c = "";
+            try { LIMITE = 20; } catch (e) { a = e.kind; }
+            try { naoDeclarada = 1; } catch (e) { b = e.kind; }
+            try { let d = 1; let d = 2; } catch (e) { c = e.kind; }
+        "#);
+
+        assert_eq!(get(&interpreter, "LIMITE"), Value::Number(10.0));
+        assert_eq!(get(&interpreter, "a"), Value::String("TypeError".into()));
+        assert_eq!(get(&interpreter, "b"), Value::String("NameError".into()));
+        assert_eq!(get(&interpreter, "c"), Value::String("NameError".into()));
+    }
+
+    #[test]
+    fn closures_capture_variables_by_reference() {
+        let interpreter = run(r#"
+            fn makeCounter() {
+                let count = 0;
+                fn inc() {
+                    count = count + 1;
+                    return count;
+                }
+                return inc;
+            }
+
+            let next = makeCounter();
+            next();
+            next();
+            let third = next();
+
+            let other = makeCounter();
+            let fresh = other();
+
+            let total = 0;
+            fn add(n) { total = total + n; }
+            add(5);
+            add(7);
+
+            let late = 1;
+            fn readLate() { return late; }
+            late = 2;
+            let seenLate = readLate();
+        "#);
+
+        assert_eq!(get(&interpreter, "third"), Value::Number(3.0));
+        assert_eq!(get(&interpreter, "fresh"), Value::Number(1.0));
+        assert_eq!(get(&interpreter, "total"), Value::Number(12.0));
+        assert_eq!(get(&interpreter, "seenLate"), Value::Number(2.0));
+    }
+
+    #[test]
+    fn recursive_and_mutually_recursive_functions() {
+        let interpreter = run(r#"
+            fn fact(n) {
+                if (n <= 1) { return 1; }
+                return n * fact(n - 1);
+            }
+
+            fn isEven(n) {
+                if (n == 0) { return true; }
+                return isOdd(n - 1);
+            }
+
+            fn isOdd(n) {
+                if (n == 0) { return false; }
+                return isEven(n - 1);
+            }
+
+            let f = fact(10);
+            let even = isEven(10);
+            let odd = isOdd(7);
+        "#);
+
+        assert_eq!(get(&interpreter, "f"), Value::Number(3628800.0));
+        assert_eq!(get(&interpreter, "even"), Value::Boolean(true));
+        assert_eq!(get(&interpreter, "odd"), Value::Boolean(true));
+    }
+
+    #[test]
+    fn deep_recursion_is_a_catchable_error() {
+        // Como no `main`, numa thread com a pilha do interpretador
+        let result = std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn(|| {
+                let interpreter = run(r#"
+                    fn depth(n) {
+                        if (n == 0) { return 0; }
+                        return 1 + depth(n - 1);
+                    }
+                    fn forever(n) { return forever(n + 1); }
+                    // Cada chamada aninha vários blocos: a pilha precisa caber no limite mesmo assim
+                    struct Walker {
+                        fn walk(n) {
+                            return match (n) {
+                                _ => {
+                                    let r = 0;
+                                    for (let i = 0; i < 1; i++) {
+                                        try { r = 1 + this.walk(n + 1); } catch (e) { throw e; }
+                                    }
+                                    r
+                                }
+                            };
+                        }
+                    }
+
+                    let deep = depth(1500);
+                    let kind = "";
+                    let message = "";
+                    try { forever(0); } catch (e) { kind = e.kind; message = e.message; }
+                    // A contagem volta ao normal depois do erro
+                    let again = depth(1500);
+                    let nested = "";
+                    try { Walker().walk(0); } catch (e) { nested = e.kind; }
+                "#);
+                ["deep", "kind", "message", "again", "nested"].map(|name| get(&interpreter, name).as_string())
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert_eq!(result, ["1500", "RecursionError", "Maximum call depth exceeded", "1500", "RecursionError"]);
+    }
+
+    #[test]
+    fn missing_arguments_are_null_and_extra_ones_an_error() {
+        let interpreter = run(r#"
+            fn pair(a, b) { return [a, b]; }
+            let partial = pair(1);
+            let none = pair();
+        "#);
+        assert_eq!(get(&interpreter, "partial").as_string(), "[1, null]");
+        assert_eq!(get(&interpreter, "none").as_string(), "[null, null]");
+
+        let cases = [
+            ("fn f(a, b) { return b; } f(1, 2, 3);", "ArityError: 'f' expects at most 2 arguments, got 3", (1, 26)),
+            ("let g = x => x;\ng(1, 2);", "ArityError: 'g' expects at most 1 argument, got 2", (2, 1)),
+            ("let o = { h: fn () {} };\no.h(1);", "ArityError: 'h' expects at most 0 arguments, got 1", (2, 1)),
+            ("struct P { x, fn init() {} }\nP(1);", "ArityError: P expects at most 0 arguments, got 1", (2, 1)),
+        ];
+        for (source, expected, position) in cases {
+            let err = Interpreter::new().execute(parse(source)).expect_err(source);
+            let span = err.span.expect("arity errors point at the call");
+            assert_eq!((err.to_string().as_str(), (span.line, span.column)), (expected, position), "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn functions_are_first_class_values() {
+        let interpreter = run(r#"
+            fn sumBy(items, f) {
+                let total = 0;
+                for (let i = 0; i < 3; i = i + 1) {
+                    total = total + f(items[i]);
+                }
+                return total;
+            }
+
+            let double = (x) => x * 2;
+            let handlers = [fn (x) { return x + 1; }, double];
+            let a = handlers[0](10);
+            let b = handlers[1](10);
+
+            fn adder(n) { return (x) => x + n; }
+            let c = adder(3)(4);
+
+            let d = (fn (x, y) { return x * y; })(6, 7);
+            let e = sumBy([1, 2, 3], x => x * x);
+            let f = "";
+            try { let notFn = 1; notFn(); } catch (err) { f = err.kind; }
+        "#);
+
+        assert_eq!(get(&interpreter, "a"), Value::Number(11.0));
+        assert_eq!(get(&interpreter, "b"), Value::Number(20.0));
+        assert_eq!(get(&interpreter, "c"), Value::Number(7.0));
+        assert_eq!(get(&interpreter, "d"), Value::Number(42.0));
+        assert_eq!(get(&interpreter, "e"), Value::Number(14.0));
+        assert_eq!(get(&interpreter, "f"), Value::String("TypeError".into()));
+    }
+
+    #[test]
+    fn objects_support_literals_access_and_mutation() {
+        let interpreter = run(r#"
+            let nome = "Ana";
+            let user = { nome, idade: 30, "e-mail": "ana@adg.dev", endereco: { cidade: "Recife" } };
+
+            let a = user.nome;
+            let b = user["e-mail"];
+            let c = user.endereco.cidade;
+            let d = user.telefone;
+
+            user.idade = user.idade + 1;
+            user.endereco.cidade = "Olinda";
+            user.ativo = true;
+
+            let alias = user;
+            alias.nome = "Bia";
+
+            let text = "" + user;
+            let same = { x: 1, y: [1, 2] } == { x: 1, y: [1, 2] };
+            let unordered = { x: 1, y: 2 } == { y: 2, x: 1 };
+            let empty = "" + {};
+            let sq = math.sqrt(16);
+
+            let opts = { default: 1, match: 2, class: "x" };
+            opts.finally = opts.default + opts.match;
+            let fallback = match (opts) { { default: d } => d, _ => 0 };
+            let keywords = [opts.class, opts.finally, fallback, opts["match"]];
+        "#);
+
+        assert_eq!(get(&interpreter, "a"), Value::String("Ana".into()));
+        assert_eq!(get(&interpreter, "b"), Value::String("ana@adg.dev".into()));
+        assert_eq!(get(&interpreter, "c"), Value::String("Recife".into()));
+        assert_eq!(get(&interpreter, "d"), Value::Null);
+        assert_eq!(
+            get(&interpreter, "text"),
+            Value::String("{ nome: Bia, idade: 31, e-mail: ana@adg.dev, endereco: { cidade: Olinda }, ativo: true }".into())
+        );
+        assert_eq!(get(&interpreter, "same"), Value::Boolean(true));
+        assert_eq!(get(&interpreter, "unordered"), Value::Boolean(true));
+        assert_eq!(get(&interpreter, "empty"), Value::String("{}".into()));
+        assert_eq!(get(&interpreter, "sq"), Value::Number(4.0));
+        assert_eq!(get(&interpreter, "keywords").as_string(), "[x, 3, 1, 2]");
+    }
+
+    #[test]
+    fn cyclic_objects_print_and_compare() {
+        let interpreter = run(r#"
+            let o = { a: 1 };
+            o.me = o;
+            let p = { a: 1 };
+            p.me = p;
+            let q = { a: 2 };
+            q.me = q;
+
+            // Ciclo indireto, passando por outro objeto
+            let pai = { nome: "pai" };
+            pai.filho = { nome: "filho", pai };
+
+            struct Node { value, next }
+            let node = Node(1);
+            node.next = node;
+
+            let text = `${o} | ${pai} | ${node}`;
+            let same = o == p;
+            let different = o == q;
+            let nodes = node == Node(1, node);
+        "#);
+
+        assert_eq!(
+            get(&interpreter, "text"),
+            Value::String([
+                "{ a: 1, me: [Circular] } | ",
+                "{ nome: pai, filho: { nome: filho, pai: [Circular] } } | ",
+                "Node { value: 1, next: [Circular] }",
+            ].concat())
+        );
+        assert_eq!(get(&interpreter, "same"), Value::Boolean(true));
+        assert_eq!(get(&interpreter, "different"), Value::Boolean(false));
+        assert_eq!(get(&interpreter, "nodes"), Value::Boolean(true));
+    }
+
+    #[test]
+    fn cyclic_arrays_print_and_compare() {
+        let interpreter = run(r#"
+            let a = [1];
+            let b = [a];
+            a[0] = b;
+            let equal = a == b;
+
+            let self_ref = [1, 2];
+            self_ref[2] = self_ref;
+            let other = [1, 2];
+            other[2] = other;
+            let same = self_ref == other;
+            other[0] = 9;
+            let changed = self_ref == other;
+
+            let mixed = { items: [] };
+            mixed.items[0] = mixed;
+
+            let text = `${self_ref} | ${a} | ${mixed}`;
+        "#);
+
+        assert_eq!(get(&interpreter, "equal"), Value::Boolean(true));
+        assert_eq!(get(&interpreter, "same"), Value::Boolean(true));
+        assert_eq!(get(&interpreter, "changed"), Value::Boolean(false));
+        assert_eq!(
+            get(&interpreter, "text"),
+            Value::String("[1, 2, [Circular]] | [[[Circular]]] | { items: [[Circular]] }".into())
+        );
+    }
+
+    #[test]
+    fn index_and_member_assignment_mutate_in_place() {
+        let interpreter = run(r#"
+            let arr = [1, 2, 3];
+            arr[0] = 10;
+            arr[1] += 5;
+            arr[2]--;
+            arr[5] = 6;
+
+            let grid = [[0, 0], [0, 0]];
+            grid[1][0] = 7;
+
+            let alias = arr;
+            alias[3] = "shared";
+
+            let obj = { count: 0, tags: [] };
+            obj.count += 2;
+            obj["count"] *= 10;
+            obj.tags[0] = "a";
+            obj["novo"] = true;
+
+            let i = 0;
+            let post = i++;
+            let pre = ++i;
+            let s = "a";
+            s += "b";
+            let n = 10;
+            n -= 4;
+            n /= 2;
+            n %= 2;
+
+            let err = "";
+            try { arr[-1] = 0; } catch (e) { err = e.kind; }
+        "#);
+
+        assert_eq!(get(&interpreter, "arr").as_string(), "[10, 7, 2, shared, null, 6]");
+        assert_eq!(get(&interpreter, "grid").as_string(), "[[0, 0], [7, 0]]");
+        assert_eq!(get(&interpreter, "obj").as_string(), "{ count: 20, tags: [a], novo: true }");
+        assert_eq!(get(&interpreter, "post"), Value::Number(0.0));
+        assert_eq!(get(&interpreter, "pre"), Value::Number(2.0));
+        assert_eq!(get(&interpreter, "i"), Value::Number(2.0));
+        assert_eq!(get(&interpreter, "s"), Value::String("ab".into()));
+        assert_eq!(get(&interpreter, "n"), Value::Number(1.0));
+        assert_eq!(get(&interpreter, "err"), Value::String("IndexError".into()));
+    }
+
+    #[test]
+    fn arrays_are_shared_by_reference() {
+        let interpreter = run(r#"
+            let items = [];
+            let copy = items;
+            for (let i = 0; i < 3; i++) {
+                items[i] = i * i;
+            }
+            let same = items == copy;
+        "#);
+
+        assert_eq!(get(&interpreter, "copy").as_string(), "[0, 1, 4]");
+        assert_eq!(get(&interpreter, "same"), Value::Boolean(true));
+    }
+
+    #[test]
+    fn template_strings_interpolate_expressions() {
+        let interpreter = run(r#"
+            let item = { nome: 'Café', preco: 4.5 };
+            let qtd = 2;
+            let linha = `${qtd}x ${item.nome} = R$ ${item.preco * qtd}`;
+            let multi = `a
+b\t${ [1, 2][1] }`;
+            let aninhada = `<${ `${qtd}-${"}"}` }>`;
+        "#);
+
+        assert_eq!(get(&interpreter, "linha"), Value::String("2x Café = R$ 9".into()));
+        assert_eq!(get(&interpreter, "multi"), Value::String("a\nb\t2".into()));
+        assert_eq!(get(&interpreter, "aninhada"), Value::String("<2-}>".into()));
+    }
+
+    #[test]
+    fn assertions_throw_catchable_assertion_errors() {
+        let interpreter = run(r#"
+            assert(1 < 2);
+            assert_eq([1, "a"], [1, "a"]);
+
+            let kind = "";
+            let message = "";
+            try {
+                assert_eq(1 + 1, 3);
+            } catch (e) {
+                kind = e.kind;
+                message = e.message;
+            }
+
+            let custom = "";
+            try { assert(false, "deu ruim"); } catch (e) { custom = e.message; }
+        "#);
+
+        assert_eq!(get(&interpreter, "kind"), Value::String("AssertionError".into()));
+        assert_eq!(get(&interpreter, "message"), Value::String("Expected 3, got 2".into()));
+        assert_eq!(get(&interpreter, "custom"), Value::String("deu ruim".into()));
+    }
+
+    #[test]
+    fn globals_can_be_defined_by_the_host() {
+        let mut interpreter = Interpreter::new();
+        interpreter.define_global("args", Value::array(vec![Value::String("-v".into())]));
+        interpreter.execute(parse("let first = args[0];")).unwrap_or_else(|err| panic!("{}", err));
+
+        assert_eq!(get(&interpreter, "first"), Value::String("-v".into()));
+    }
+
+    // Cria os arquivos numa pasta temporária própria do teste e devolve o caminho dela
+    fn module_dir(test: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("adg-{}-{}", test, std::process::id()));
+        for (name, code) in files {
+            let path = dir.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, code).unwrap();
+        }
+        dir
+    }
+
+    fn run_main(dir: &Path) -> Result<Interpreter, String> {
+        let path = dir.join("main.adg").display().to_string();
+        let code = fs::read_to_string(&path).unwrap();
+        let mut interpreter = Interpreter::new();
+        match interpreter.run_file(&path, &code) {
+            Ok(()) => Ok(interpreter),
+            Err(errors) => Err(errors[0].report_in(interpreter.sources())),
+        }
+    }
+
+    #[test]
+    fn imports_exports_from_relative_files_once() {
+        let dir = module_dir("imports", &[
+            ("main.adg", r#"
+                import { dobro, PI as pi } from "./lib/util.adg";
+                import util from "./lib/util.adg";
+                import { leituras } from "./lib/estado.adg";
+                let resultado = [dobro(pi), util.dobro(1), util.interno];
+                let cargas = leituras.total;
+            "#),
+            ("lib/util.adg", r#"
+                import { leituras } from "../lib/./estado.adg";
+                leituras.total += 1;
+                let interno = "privado";
+                export const PI = 3;
+                export fn dobro(x) { return x * 2; }
+            "#),
+            ("lib/estado.adg", "export let leituras = { total: 0 };"),
+        ]);
+
+        let interpreter = run_main(&dir).unwrap_or_else(|err| panic!("{}", err));
+        // Só os nomes exportados aparecem no módulo
+        assert_eq!(get(&interpreter, "resultado").as_string(), "[6, 2, null]");
+        // util.adg foi importado duas vezes, mas rodou uma só
+        assert_eq!(get(&interpreter, "cargas"), Value::Number(1.0));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn module_exports_are_read_only() {
+        let dir = module_dir("read-only", &[
+            ("util.adg", r#"
+                export let contador = 0;
+                export let config = { nivel: 1 };
+                export fn incrementar() { contador += 1; }
+            "#),
+        ]);
+        let cases = [
+            "util.contador = 5;",
+            "util.contador++;",
+            "util[\"novo\"] = 1;",
+        ];
+
+        for (index, case) in cases.iter().enumerate() {
+            fs::write(dir.join("main.adg"), format!("import util from \"./util.adg\";\n{}", case)).unwrap();
+            let err = run_main(&dir).err().unwrap_or_else(|| panic!("{} should fail", case));
+            assert!(err.contains("TypeError: Cannot assign to"), "case {}: {}", index, err);
+            assert!(err.contains("module exports are read-only"), "case {}: {}", index, err);
+        }
+
+        fs::write(dir.join("main.adg"), r#"
+            import util from "./util.adg";
+            import { contador, incrementar, config } from "./util.adg";
+            incrementar();
+            // Os exports são os valores no fim da execução do módulo
+            let valores = [contador, util.contador];
+            // Os objetos exportados continuam mutáveis e compartilhados
+            util.config.nivel = 2;
+            let nivel = config.nivel;
+        "#).unwrap();
+        let interpreter = run_main(&dir).unwrap_or_else(|err| panic!("{}", err));
+        assert_eq!(get(&interpreter, "valores").as_string(), "[0, 0]");
+        assert_eq!(get(&interpreter, "nivel"), Value::Number(2.0));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn import_errors() {
+        let cases = [
+            ("import { nada } from \"./b.adg\";", "ImportError: Module './b.adg' has no export 'nada'"),
+            ("import x from \"./nao_existe.adg\";", "ImportError: Cannot find module"),
+            ("import x from \"http\";", "ImportError: Unknown module 'http'"),
+            ("import c from \"./c.adg\";", "ImportError: Circular import: "),
+            ("import x from \"./quebrado.adg\";", "--> "),
+        ];
+
+        for (main, expected) in cases {
+            let dir = module_dir("import-errors", &[
+                ("main.adg", main),
+                ("b.adg", "export let b = 1;"),
+                ("c.adg", "import d from \"./d.adg\";"),
+                ("d.adg", "import c from \"./c.adg\";"),
+                ("quebrado.adg", "export let ok = 1;\nlet = 2;"),
+            ]);
+            let err = run_main(&dir).err().unwrap_or_else(|| panic!("{} should fail", main));
+            assert!(err.contains(expected), "{}: {}", main, err);
+            fs::remove_dir_all(&dir).unwrap();
+        }
+
+        let dir = module_dir("import-cycle", &[
+            ("main.adg", "import c from \"./c.adg\";"),
+            ("c.adg", "import d from \"./d.adg\";"),
+            ("d.adg", "let x = 1;\nimport c from \"./c.adg\";"),
+        ]);
+        let err = run_main(&dir).err().unwrap();
+        let c = dir.join("c.adg").display().to_string();
+        let d = dir.join("d.adg").display().to_string();
+        assert!(err.contains(&format!("Circular import: {} -> {} -> {}", c, d, c)), "{}", err);
+        // O erro aponta para o import dentro de d.adg
+        assert!(err.contains(&format!("--> {}:2:1", d)), "{}", err);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let dir = module_dir("import-syntax", &[
+            ("main.adg", "import x from \"./quebrado.adg\";"),
+            ("quebrado.adg", "export let ok = 1;\nlet = 2;"),
+        ]);
+        let err = run_main(&dir).err().unwrap();
+        assert!(err.contains(&format!("--> {}:2:5", dir.join("quebrado.adg").display())), "{}", err);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn native_modules_are_importable() {
+        let interpreter = run(r#"
+            import { sqrt } from "math";
+            import m from "math";
+            let r = sqrt(m.pow(3, 2));
+        "#);
+        assert_eq!(get(&interpreter, "r"), Value::Number(3.0));
+    }
+
+    #[test]
+    fn structs_construct_instances_with_methods() {
+        let interpreter = run(r#"
+            struct Point {
+                x,
+                y = 0,
+
+                fn norm() {
+                    return math.sqrt(this.x * this.x + self.y * self.y);
+                }
+
+                fn move(dx, dy) {
+                    this.x += dx;
+                    this.y += dy;
+                    return this;
+                }
+            }
+
+            class Counter {
+                count = 0,
+                step,
+                history = [],
+
+                fn init(step) {
+                    this.step = step;
+                }
+
+                fn tick() {
+                    this.count += this.step;
+                    this.history[0] = this.count;
+                    return this.count;
+                }
+            }
+
+            let p = Point(3, 4);
+            let norm = p.norm();
+            let moved = p.move(1, 1).move(1, 1).x;
+            let text = `${Point(1, 2)} ${Point(7)}`;
+            let bound = Point(6, 8).norm;
+            let detached = bound();
+
+            let a = Counter(5);
+            let b = Counter(2);
+            a.tick();
+            let ticks = a.tick() + b.tick();
+            let shared = a.history == b.history;
+            let equal = Point(1, 2) == Point(1, 2);
+            let different = Point(1, 2) == Point(1, 3);
+        "#);
+
+        assert_eq!(get(&interpreter, "norm"), Value::Number(5.0));
+        assert_eq!(get(&interpreter, "moved"), Value::Number(5.0));
+        assert_eq!(get(&interpreter, "text"), Value::String("Point { x: 1, y: 2 } Point { x: 7, y: 0 }".into()));
+        assert_eq!(get(&interpreter, "detached"), Value::Number(10.0));
+        assert_eq!(get(&interpreter, "ticks"), Value::Number(12.0));
+        assert_eq!(get(&interpreter, "shared"), Value::Boolean(false));
+        assert_eq!(get(&interpreter, "equal"), Value::Boolean(true));
+        assert_eq!(get(&interpreter, "different"), Value::Boolean(false));
+        assert_eq!(get(&interpreter, "p").as_string(), "Point { x: 5, y: 6 }");
+        assert_eq!(get(&interpreter, "Counter").as_string(), "[Struct Counter]");
+    }
+
+    #[test]
+    fn struct_fields_are_fixed() {
+        let cases = [
+            ("p.z = 1;", "TypeError: Point has no field 'z'"),
+            (r#"p["z"] = 1;"#, "TypeError: Point has no field 'z'"),
+            ("p.z;", "TypeError: Point has no field or method 'z'"),
+            ("Point(1, 2, 3);", "ArityError: Point expects at most 2 arguments, got 3"),
+            ("p.x();", "TypeError: 'x' is not a function, got number"),
+        ];
+
+        for (source, expected) in cases {
+            let source = format!("struct Point {{ x, y }} let p = Point(1, 2); {}", source);
+            let err = Interpreter::new().execute(parse(&source)).expect_err(&source);
+            assert_eq!(err.to_string(), expected, "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn enums_carry_payloads_and_match_by_variant() {
+        let interpreter = run(r#"
+            enum Shape {
+                Circle(r),
+                Rect(w, h),
+                Empty,
+            }
+
+            fn area(s) {
+                return match (s) {
+                    Shape.Circle(r) => 3 * r * r,
+                    Shape.Rect(w, h) if (w == h) => {
+                        let side = w;
+                        side * side
+                    },
+                    Shape.Rect(w, h) => w * h,
+                    Shape.Empty => 0,
+                };
+            }
+
+            let areas = [area(Shape.Circle(2)), area(Shape.Rect(3, 3)), area(Shape.Rect(2, 5)), area(Shape.Empty)];
+            let text = `${Shape.Rect(2, 5)} ${Shape.Empty} ${Shape.Circle}`;
+            let width = Shape.Rect(2, 5).w;
+            let same = Shape.Circle(1) == Shape.Circle(1) && Shape.Empty == Shape.Empty;
+            let different = Shape.Circle(1) == Shape.Circle(2);
+
+            // Como statement, os braços podem usar `return`
+            fn kind(s) {
+                match (s) {
+                    Shape.Circle => { return "circle"; }
+                    _ => {}
+                }
+                return "other";
+            }
+            let kinds = [kind(Shape.Circle(1)), kind(Shape.Empty)];
+        "#);
+
+        assert_eq!(get(&interpreter, "areas").as_string(), "[12, 9, 10, 0]");
+        assert_eq!(get(&interpreter, "text"), Value::String("Shape.Rect(2, 5) Shape.Empty [Variant Shape.Circle]".into()));
+        assert_eq!(get(&interpreter, "width"), Value::Number(2.0));
+        assert_eq!(get(&interpreter, "same"), Value::Boolean(true));
+        assert_eq!(get(&interpreter, "different"), Value::Boolean(false));
+        assert_eq!(get(&interpreter, "kinds").as_string(), "[circle, other]");
+        assert!(interpreter.warnings.is_empty(), "{:?}", interpreter.warnings);
+    }
+
+    #[test]
+    fn match_destructures_arrays_objects_and_literals() {
+        let interpreter = run(r#"
+            fn describe(v) {
+                return match (v) {
+                    0 => "zero",
+                    -1 => "minus one",
+                    "oi" => "greeting",
+                    true => "yes",
+                    [] => "empty",
+                    [x] => `one ${x}`,
+                    [a, b, ...rest] => `${a}, ${b} and ${rest}`,
+                    { kind: "user", name } => `user ${name}`,
+                    { name } => `thing ${name}`,
+                    other => `other ${other}`
+                };
+            }
+
+            struct Point { x, y }
+            let results = [
+                describe(0), describe(-1), describe("oi"), describe(true), describe([]), describe([7]),
+                describe([1, 2, 3, 4]), describe([1, 2]), describe({ kind: "user", name: "Ana" }),
+                describe({ name: "Bia" }), describe({ kind: "user" }), describe(5),
+                match (Point(1, 2)) { { x, y } => x + y },
+            ];
+
+            // As variáveis do padrão só existem dentro do braço
+            let x = "outer";
+            let inner = match ([1]) { [x] => x };
+        "#);
+
+        assert_eq!(get(&interpreter, "results").as_string(), [
+            "[zero, minus one, greeting, yes, empty, one 7, 1, 2 and [3, 4], 1, 2 and [], user Ana,",
+            " thing Bia, other { kind: user }, other 5, 3]",
+        ].concat());
+        assert_eq!(get(&interpreter, "x"), Value::String("outer".into()));
+        assert_eq!(get(&interpreter, "inner"), Value::Number(1.0));
+    }
+
+    #[test]
+    fn null_pattern_matches_only_null() {
+        let interpreter = run(r#"
+            fn describe(v) {
+                return match (v) {
+                    null => "nothing",
+                    other => `value ${other}`
+                };
+            }
+            let results = [describe(null), describe(0), describe(false), describe(""), describe({}.nada)];
+        "#);
+
+        assert_eq!(get(&interpreter, "results").as_string(), "[nothing, value 0, value false, value , nothing]");
+    }
+
+    #[test]
+    fn non_exhaustive_enum_matches_warn_once() {
+        let interpreter = run(r#"
+            enum Light { Red, Yellow, Green(seconds) }
+            fn go(light) {
+                return match (light) {
+                    Light.Green(s) if (s > 0) => true,
+                    Light.Red => false,
+                };
+            }
+            go(Light.Red);
+            go(Light.Red);
+            let full = match (Light.Yellow) { Light.Yellow => 1, _ => 2 };
+        "#);
+
+        let warnings: Vec<&str> = interpreter.warnings.iter().map(|(_, message)| message.as_str()).collect();
+        assert_eq!(warnings, vec!["Non-exhaustive match on Light: Light.Yellow, Light.Green not covered"]);
+    }
+
+    #[test]
+    fn match_and_variant_errors() {
+        let cases = [
+            ("match (3) { 1 => 1, \"3\" => 3 };", "MatchError: No match arm for 3"),
+            ("Shape.Rect(1);", "ArityError: Shape.Rect expects 2 arguments, got 1"),
+            ("Shape.Square;", "TypeError: Shape has no variant 'Square'"),
+            ("Shape.Rect(1, 2).r;", "TypeError: Shape.Rect has no field 'r'"),
+            ("match (1) { Shape.Square => 1 };", "TypeError: Shape has no variant 'Square'"),
+            ("match (Shape.Empty) { Shape.Rect(w) => 1, _ => 2 };", "TypeError: Pattern Shape.Rect expects 2 fields, got 1"),
+            ("let n = 1; match (1) { n.A => 1 };", "TypeError: 'n' is not an enum, got number"),
+        ];
+
+        for (source, expected) in cases {
+            let source = format!("enum Shape {{ Rect(w, h), Empty }} {}", source);
+            let err = Interpreter::new().execute(parse(&source)).expect_err(&source);
+            assert_eq!(err.to_string(), expected, "source: {}", source);
+        }
+    }
 }
@@ -1,3 +1,6 @@
+use crate::span::Span;
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     Looping,
@@ -7,67 +10,252 @@ pub enum Token {
     If,
     Else,
     While,
+    Do,
     For,
     Return,
     Throw,
     Try,
     Catch,
+    Finally,
     Switch,
     Case,
+    Default,
     Break,
     Continue,
+    Import,
+    Export,
+    Struct,
+    Class,
+    Enum,
+    Match,
     Identifier(String),
     Number(f64),
     String(String),
+    Template(Vec<TemplatePart>),
     Boolean(bool),
+    Null,
     Operator(String),
     Symbol(char),
     EOF,
     Unknown(String),
+    // Literal malformado; o parser o reporta como erro de sintaxe
+    Error(String),
+    // Literal ou comentário sem fim, que consumiu o resto do arquivo
+    Unterminated(String),
+}
+
+// Palavras reservadas e o token de cada uma
+const KEYWORDS: [(&str, Token); 28] = [
+    ("looping", Token::Looping),
+    ("let", Token::Let),
+    ("const", Token::Const),
+    ("fn", Token::Fn),
+    ("if", Token::If),
+    ("else", Token::Else),
+    ("while", Token::While),
+    ("do", Token::Do),
+    ("for", Token::For),
+    ("return", Token::Return),
+    ("throw", Token::Throw),
+    ("try", Token::Try),
+    ("catch", Token::Catch),
+    ("finally", Token::Finally),
+    ("switch", Token::Switch),
+    ("case", Token::Case),
+    ("default", Token::Default),
+    ("break", Token::Break),
+    ("continue", Token::Continue),
+    ("import", Token::Import),
+    ("export", Token::Export),
+    ("struct", Token::Struct),
+    ("class", Token::Class),
+    ("enum", Token::Enum),
+    ("match", Token::Match),
+    ("true", Token::Boolean(true)),
+    ("false", Token::Boolean(false)),
+    ("null", Token::Null),
+];
+
+impl Token {
+    /// Token da palavra reservada `name`, se for uma.
+    pub fn keyword(name: &str) -> Option<Token> {
+        KEYWORDS.iter().find(|(keyword, _)| *keyword == name).map(|(_, token)| token.clone())
+    }
+
+    /// Texto da palavra reservada, para os lugares onde ela vale como nome
+    /// (`obj.default`, `{ match: 1 }`).
+    pub fn keyword_name(&self) -> Option<&'static str> {
+        KEYWORDS.iter().find(|(_, token)| token == self).map(|(keyword, _)| *keyword)
+    }
+}
+
+/// Pedaço de uma template string: texto literal ou o código de um `${...}`,
+/// junto com o trecho do código-fonte onde ele está.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplatePart {
+    Text(String),
+    Code(String, Span),
+}
+
+// Erro ao ler um token, que vira `Token::Error` ou `Token::Unterminated`
+enum LexError {
+    Invalid(String),
+    Unterminated(String),
+}
+
+impl From<String> for LexError {
+    fn from(message: String) -> Self {
+        LexError::Invalid(message)
+    }
+}
+
+impl From<LexError> for Token {
+    fn from(error: LexError) -> Self {
+        match error {
+            LexError::Invalid(message) => Token::Error(message),
+            LexError::Unterminated(message) => Token::Unterminated(message),
+        }
+    }
 }
 
 pub struct Lexer {
     input: Vec<char>,
     position: usize,
+    // Deslocamento em bytes de cada caractere (e do fim da entrada)
+    offsets: Vec<usize>,
+    // Índices dos caracteres que começam cada linha
+    line_starts: Vec<usize>,
+    // Onde a entrada começa no arquivo original (código de um `${...}`)
+    origin: Span,
+    span: Span,
+    // Comentários `///` lidos antes do token atual
+    pending_docs: Vec<String>,
+    doc: Option<String>,
 }
 
 impl Lexer {
     pub fn new(source: &str) -> Self {
+        Lexer::with_origin(source, Span::default())
+    }
+
+    /// Lexer para um trecho extraído de um arquivo maior, que começa em `origin`;
+    /// os spans produzidos apontam para o arquivo original.
+    pub fn with_origin(source: &str, origin: Span) -> Self {
+        let input: Vec<char> = source.chars().collect();
+
+        let mut offsets = Vec::with_capacity(input.len() + 1);
+        let mut line_starts = vec![0];
+        let mut offset = 0;
+        for (i, c) in input.iter().enumerate() {
+            offsets.push(offset);
+            offset += c.len_utf8();
+            if *c == '\n' {
+                line_starts.push(i + 1);
+            }
+        }
+        offsets.push(offset);
+
         Lexer {
-            input: source.chars().collect(),
+            input,
             position: 0,
+            offsets,
+            line_starts,
+            origin,
+            span: origin,
+            pending_docs: vec![],
+            doc: None,
         }
     }
 
     pub fn next_token(&mut self) -> Token {
-        self.skip_whitespace();
+        let (start, result) = match self.skip_trivia() {
+            Ok(()) => {
+                // Os comentários de documentação ficam associados ao token que vem a seguir
+                self.doc = if self.pending_docs.is_empty() {
+                    None
+                } else {
+                    Some(self.pending_docs.join("\n"))
+                };
+                self.pending_docs.clear();
+                (self.position, self.scan_token())
+            }
+            Err((start, message)) => (start, Err(LexError::Unterminated(message))),
+        };
+
+        self.span = self.span_between(start, self.position);
+        result.unwrap_or_else(Token::from)
+    }
+
+    /// Trecho do código ocupado pelo último token lido.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    fn span_between(&self, start: usize, end: usize) -> Span {
+        let line = self.line_starts.partition_point(|&s| s <= start);
+        let column = start - self.line_starts[line - 1] + 1;
+        let (line, column) = if line == 1 {
+            (self.origin.line, self.origin.column + column - 1)
+        } else {
+            (self.origin.line + line - 1, column)
+        };
 
+        Span {
+            start: self.origin.start + self.offsets[start],
+            end: self.origin.start + self.offsets[end],
+            line,
+            column,
+        }
+    }
+
+    fn scan_token(&mut self) -> Result<Token, LexError> {
         if self.position >= self.input.len() {
-            return Token::EOF;
+            return Ok(Token::EOF);
         }
 
         let current = self.input[self.position];
 
         // Número
         if current.is_ascii_digit() {
-            return self.lex_number();
+            return Ok(self.lex_number()?);
         }
 
         // Identificador ou palavra-chave
         if current.is_ascii_alphabetic() || current == '_' {
-            return self.lex_identifier_or_keyword();
+            return Ok(self.lex_identifier_or_keyword());
         }
 
         // String
-        if current == '"' {
-            return self.lex_string();
+        if current == '"' || current == '\'' {
+            return self.lex_string(current);
+        }
+
+        // Template string
+        if current == '`' {
+            return self.lex_template();
         }
 
         // Operadores e símbolos
-        match current {
-            '+' | '-' | '*' | '/' => {
+        let token = match current {
+            '+' | '-' if self.peek_char() == Some(current) => {
+                self.position += 2;
+                Token::Operator(format!("{}{}", current, current))
+            }
+
+            '+' | '-' | '*' | '/' | '%' => {
+                let mut op = current.to_string();
+                // Atribuição composta: +=, -=, *=, /=, %=
+                if self.peek_char() == Some('=') {
+                    self.position += 1;
+                    op.push('=');
+                }
                 self.position += 1;
-                Token::Operator(current.to_string())
+                Token::Operator(op)
+            }
+
+            '=' if self.peek_char() == Some('>') => {
+                self.position += 2;
+                Token::Operator("=>".to_string())
             }
 
             '=' | '!' | '<' | '>' => {
@@ -80,6 +268,11 @@ impl Lexer {
                 Token::Operator(op)
             }
 
+            '&' | '|' if self.peek_char() == Some(current) => {
+                self.position += 2;
+                Token::Operator(format!("{}{}", current, current))
+            }
+
             '{' | '}' | '(' | ')' | '[' | ']' | ';' | ':' | ',' | '.' => {
                 self.position += 1;
                 Token::Symbol(current)
@@ -89,25 +282,115 @@ impl Lexer {
                 self.position += 1;
                 Token::Unknown(current.to_string())
             }
-        }
+        };
+        Ok(token)
     }
 
-    fn lex_number(&mut self) -> Token {
+    /// Documentação (`///`) escrita logo antes do último token lido.
+    pub fn take_doc(&mut self) -> Option<String> {
+        self.doc.take()
+    }
+
+    // Posição atual, para o parser poder voltar atrás (lookahead)
+    pub fn checkpoint(&self) -> usize {
+        self.position
+    }
+
+    pub fn rewind(&mut self, checkpoint: usize) {
+        self.position = checkpoint;
+    }
+
+    // Decimais (`1_000.5e-3`) e inteiros com prefixo (`0xff`, `0b1010`, `0o755`)
+    fn lex_number(&mut self) -> Result<Token, String> {
         let start = self.position;
 
-        while self.position < self.input.len() && self.input[self.position].is_ascii_digit() {
+        let radix = match (self.input[start], self.peek_char()) {
+            ('0', Some('x' | 'X')) => Some((16, "hexadecimal")),
+            ('0', Some('b' | 'B')) => Some((2, "binary")),
+            ('0', Some('o' | 'O')) => Some((8, "octal")),
+            _ => None,
+        };
+
+        if let Some((radix, kind)) = radix {
+            self.position += 2;
+            let digits = self.lex_digits(start, radix)?;
+            self.check_number_end(start, kind)?;
+            if digits.is_empty() {
+                return Err(self.number_error(start, &format!("expected {} digits", kind)));
+            }
+            return match u64::from_str_radix(&digits, radix) {
+                Ok(value) => Ok(Token::Number(value as f64)),
+                Err(_) => Err(self.number_error(start, "value is too large")),
+            };
+        }
+
+        let mut text = self.lex_digits(start, 10)?;
+
+        if self.current_char() == Some('.') && self.peek_char().is_some_and(|c| c.is_ascii_digit()) {
             self.position += 1;
+            text.push('.');
+            text.push_str(&self.lex_digits(start, 10)?);
         }
 
-        if self.position < self.input.len() && self.input[self.position] == '.' {
+        if matches!(self.current_char(), Some('e' | 'E')) {
             self.position += 1;
-            while self.position < self.input.len() && self.input[self.position].is_ascii_digit() {
+            text.push('e');
+            if let Some(sign @ ('+' | '-')) = self.current_char() {
                 self.position += 1;
+                text.push(sign);
             }
+            let exponent = self.lex_digits(start, 10)?;
+            if exponent.is_empty() {
+                return Err(self.number_error(start, "expected digits in exponent"));
+            }
+            text.push_str(&exponent);
         }
 
-        let num_str: String = self.input[start..self.position].iter().collect();
-        Token::Number(num_str.parse().unwrap_or(0.0))
+        self.check_number_end(start, "decimal")?;
+        match text.parse() {
+            Ok(value) => Ok(Token::Number(value)),
+            Err(_) => Err(self.number_error(start, "malformed number")),
+        }
+    }
+
+    // Lê dígitos da base informada; `_` só é aceito entre dois dígitos
+    fn lex_digits(&mut self, start: usize, radix: u32) -> Result<String, String> {
+        let mut digits = String::new();
+        while let Some(c) = self.current_char() {
+            if c.is_digit(radix) {
+                digits.push(c);
+                self.position += 1;
+            } else if c == '_' {
+                let next_is_digit = self.peek_char().is_some_and(|n| n.is_digit(radix));
+                self.position += 1;
+                if digits.is_empty() || !next_is_digit {
+                    return Err(self.number_error(start, "digit separator '_' must be between digits"));
+                }
+            } else {
+                break;
+            }
+        }
+        Ok(digits)
+    }
+
+    // Um número não pode ser seguido diretamente por letras ou dígitos de outra base
+    fn check_number_end(&mut self, start: usize, kind: &str) -> Result<(), String> {
+        if let Some(c) = self.current_char()
+            && (c.is_ascii_alphanumeric() || c == '_')
+        {
+            self.position += 1;
+            return Err(self.number_error(start, &format!("invalid digit '{}' in {} literal", c, kind)));
+        }
+        Ok(())
+    }
+
+    fn number_error(&self, start: usize, reason: &str) -> String {
+        let text: String = self.input[start..self.position].iter().collect();
+        format!("Invalid number literal '{}': {}", text, reason)
+    }
+
+    fn current_char(&self) -> Option<char> {
+        self.input.get(self.position).copied()
     }
 
     fn lex_identifier_or_keyword(&mut self) -> Token {
@@ -122,47 +405,205 @@ impl Lexer {
 
         let ident: String = self.input[start..self.position].iter().collect();
 
-        match ident.as_str() {
-            "looping" => Token::Looping,
-            "let" => Token::Let,
-            "const" => Token::Const,
-            "fn" => Token::Fn,
-            "if" => Token::If,
-            "else" => Token::Else,
-            "while" => Token::While,
-            "for" => Token::For,
-            "return" => Token::Return,
-            "throw" => Token::Throw,
-            "try" => Token::Try,
-            "catch" => Token::Catch,
-            "switch" => Token::Switch,
-            "case" => Token::Case,
-            "break" => Token::Break,
-            "continue" => Token::Continue,
-            "true" => Token::Boolean(true),
-            "false" => Token::Boolean(false),
-            _ => Token::Identifier(ident),
-        }
-    }
-
-    fn lex_string(&mut self) -> Token {
-        self.position += 1; // pula o "
+        Token::keyword(&ident).unwrap_or(Token::Identifier(ident))
+    }
+
+    // Um escape inválido não interrompe a leitura: o erro cobre a string inteira
+    fn lex_string(&mut self, quote: char) -> Result<Token, LexError> {
+        self.position += 1; // pula a aspa de abertura
+        let mut content = String::new();
+        let mut error = None;
+
+        loop {
+            match self.input.get(self.position) {
+                None => return Err(LexError::Unterminated("Unterminated string literal".to_string())),
+                Some(&c) if c == quote => break,
+                Some('\\') => match self.lex_escape() {
+                    Ok(c) => content.push(c),
+                    Err(message) => {
+                        error.get_or_insert(message);
+                    }
+                },
+                Some(&c) => {
+                    content.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+        self.position += 1; // pula a aspa de fechamento
+
+        match error {
+            Some(message) => Err(LexError::Invalid(message)),
+            None => Ok(Token::String(content)),
+        }
+    }
+
+    // `texto ${expr} texto`, podendo ocupar várias linhas
+    fn lex_template(&mut self) -> Result<Token, LexError> {
+        self.position += 1; // pula a crase
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut error = None;
+
+        loop {
+            match self.input.get(self.position) {
+                None => return Err(LexError::Unterminated("Unterminated template literal".to_string())),
+                Some('`') => break,
+                Some('\\') => match self.lex_escape() {
+                    Ok(c) => text.push(c),
+                    Err(message) => {
+                        error.get_or_insert(message);
+                    }
+                },
+                Some('$') if self.peek_char() == Some('{') => {
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                    }
+                    self.position += 2;
+                    let start = self.position;
+                    let code = self.lex_interpolation()?;
+                    // `self.position` está logo após a `}` de fechamento
+                    let span = self.span_between(start, self.position - 1);
+                    parts.push(TemplatePart::Code(code, span));
+                }
+                Some(&c) => {
+                    text.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+        self.position += 1; // pula a crase de fechamento
+
+        if let Some(message) = error {
+            return Err(LexError::Invalid(message));
+        }
+        if !text.is_empty() {
+            parts.push(TemplatePart::Text(text));
+        }
+        Ok(Token::Template(parts))
+    }
+
+    // Código de um `${...}`: lê tokens até a `}` correspondente, então strings e
+    // templates aninhados dentro da expressão são tratados corretamente
+    fn lex_interpolation(&mut self) -> Result<String, LexError> {
         let start = self.position;
+        let saved_docs = std::mem::take(&mut self.pending_docs);
+        let mut depth = 0;
 
-        while self.position < self.input.len() && self.input[self.position] != '"' {
-            self.position += 1;
+        loop {
+            match self.next_token() {
+                Token::EOF => return Err(LexError::Unterminated("Unterminated template interpolation".to_string())),
+                Token::Error(message) => return Err(LexError::Invalid(message)),
+                Token::Unterminated(message) => return Err(LexError::Unterminated(message)),
+                Token::Symbol('{') => depth += 1,
+                Token::Symbol('}') if depth == 0 => break,
+                Token::Symbol('}') => depth -= 1,
+                _ => {}
+            }
         }
 
-        let content: String = self.input[start..self.position].iter().collect();
-        self.position += 1; // pula o fechamento "
+        self.pending_docs = saved_docs;
+        Ok(self.input[start..self.position - 1].iter().collect())
+    }
+
+    // Sequências de escape: \n \t \r \0 \\ \" \' \` \$ e \u{...}
+    fn lex_escape(&mut self) -> Result<char, String> {
+        self.position += 1; // pula a barra
+        let Some(&c) = self.input.get(self.position) else {
+            return Err("Unterminated string literal".to_string());
+        };
+        self.position += 1;
 
-        Token::String(content)
+        match c {
+            'n' => Ok('\n'),
+            't' => Ok('\t'),
+            'r' => Ok('\r'),
+            '0' => Ok('\0'),
+            '\\' | '"' | '\'' | '`' | '$' => Ok(c),
+            'u' => {
+                if self.input.get(self.position) != Some(&'{') {
+                    return Err("Expected '{' after \\u in unicode escape".to_string());
+                }
+                self.position += 1;
+                let start = self.position;
+                while self.position < self.input.len() && self.input[self.position] != '}' {
+                    self.position += 1;
+                }
+                if self.position >= self.input.len() {
+                    return Err("Unterminated unicode escape".to_string());
+                }
+                let hex: String = self.input[start..self.position].iter().collect();
+                self.position += 1; // pula a }
+
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|_| !hex.is_empty() && hex.len() <= 6)
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("Invalid unicode escape '\\u{{{}}}'", hex))
+            }
+            other => Err(format!("Unknown escape sequence '\\{}'", other)),
+        }
     }
 
-    fn skip_whitespace(&mut self) {
-        while self.position < self.input.len() && self.input[self.position].is_whitespace() {
+    // Pula espaços e comentários (`//`, `/* */` aninhável e `///` de documentação);
+    // um comentário de bloco sem fim é reportado a partir de onde começa
+    fn skip_trivia(&mut self) -> Result<(), (usize, String)> {
+        while self.position < self.input.len() {
+            let current = self.input[self.position];
+            if current.is_whitespace() {
+                self.position += 1;
+            } else if current == '/' && self.peek_char() == Some('/') {
+                self.skip_line_comment();
+            } else if current == '/' && self.peek_char() == Some('*') {
+                let start = self.position;
+                if !self.skip_block_comment() {
+                    return Err((start, "Unterminated block comment".to_string()));
+                }
+            } else {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_line_comment(&mut self) {
+        let start = self.position;
+        while self.position < self.input.len() && self.input[self.position] != '\n' {
             self.position += 1;
         }
+
+        let text: String = self.input[start..self.position].iter().collect();
+        // `///` é documentação; `////` volta a ser um comentário comum
+        if let Some(doc) = text.strip_prefix("///")
+            && !doc.starts_with('/')
+        {
+            let doc = doc.strip_prefix(' ').unwrap_or(doc);
+            self.pending_docs.push(doc.trim_end().to_string());
+        }
+    }
+
+    // Retorna `false` se a entrada acabar antes do comentário ser fechado
+    fn skip_block_comment(&mut self) -> bool {
+        self.position += 2; // pula o /*
+        let mut depth = 1;
+
+        while depth > 0 {
+            if self.position >= self.input.len() {
+                return false;
+            }
+            match (self.input[self.position], self.peek_char()) {
+                ('/', Some('*')) => {
+                    depth += 1;
+                    self.position += 2;
+                }
+                ('*', Some('/')) => {
+                    depth -= 1;
+                    self.position += 2;
+                }
+                _ => self.position += 1,
+            }
+        }
+        true
     }
 
     fn peek_char(&self) -> Option<char> {
@@ -173,3 +614,188 @@ impl Lexer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(source: &str) -> Vec<Token> {
+        let mut lexer = Lexer::new(source);
+        let mut tokens = vec![];
+        loop {
+            let token = lexer.next_token();
+            if token == Token::EOF {
+                return tokens;
+            }
+            tokens.push(token);
+        }
+    }
+
+    #[test]
+    fn skips_line_and_nested_block_comments() {
+        let source = "
+            let a = 10 / 2; // divide
+            /* bloco /* aninhado */ ainda comentário */
+            let b = a; /**/
+        ";
+
+        assert_eq!(tokens(source), vec![
+            Token::Let, Token::Identifier("a".into()), Token::Operator("=".into()),
+            Token::Number(10.0), Token::Operator("/".into()), Token::Number(2.0), Token::Symbol(';'),
+            Token::Let, Token::Identifier("b".into()), Token::Operator("=".into()),
+            Token::Identifier("a".into()), Token::Symbol(';'),
+        ]);
+    }
+
+    #[test]
+    fn doc_comments_attach_to_next_token() {
+        let mut lexer = Lexer::new("
+            /// Soma dois números.
+            ///
+            /// Retorna a + b.
+            //// não é documentação
+            fn soma
+            // comum
+            x
+        ");
+
+        assert_eq!(lexer.next_token(), Token::Fn);
+        assert_eq!(lexer.take_doc().as_deref(), Some("Soma dois números.\n\nRetorna a + b."));
+        assert_eq!(lexer.next_token(), Token::Identifier("soma".into()));
+        assert_eq!(lexer.take_doc(), None);
+        assert_eq!(lexer.next_token(), Token::Identifier("x".into()));
+        assert_eq!(lexer.take_doc(), None);
+    }
+
+    #[test]
+    fn string_escapes_and_quotes() {
+        assert_eq!(tokens(r#""linha\n\tcom \"aspas\" e \\ barra""#), vec![
+            Token::String("linha\n\tcom \"aspas\" e \\ barra".into()),
+        ]);
+        assert_eq!(tokens(r#"'simples "dentro" e \'escapada\''"#), vec![
+            Token::String("simples \"dentro\" e 'escapada'".into()),
+        ]);
+        assert_eq!(tokens(r#""\u{48}\u{1F600}\u{e9}""#), vec![
+            Token::String("H😀é".into()),
+        ]);
+    }
+
+    #[test]
+    fn template_literals_split_text_and_code() {
+        let source = "`Olá, ${nome}!\nTotal: ${ soma({ a: 1 }.a, `x${y}`) } \\${nada}`";
+
+        let tokens = tokens(source);
+        let [Token::Template(parts)] = tokens.as_slice() else {
+            panic!("expected a single template token");
+        };
+        let parts: Vec<(&str, Option<(usize, usize)>)> = parts.iter()
+            .map(|part| match part {
+                TemplatePart::Text(text) => (text.as_str(), None),
+                TemplatePart::Code(code, span) => (code.as_str(), Some((span.line, span.column))),
+            })
+            .collect();
+        assert_eq!(parts, vec![
+            ("Olá, ", None),
+            ("nome", Some((1, 9))),
+            ("!\nTotal: ", None),
+            (" soma({ a: 1 }.a, `x${y}`) ", Some((2, 10))),
+            (" ${nada}", None),
+        ]);
+    }
+
+    #[test]
+    fn tokens_carry_line_column_and_byte_offsets() {
+        let mut lexer = Lexer::new("let s = \"é\";\n  /* x\n */ fim");
+        let mut spans = vec![];
+        loop {
+            let token = lexer.next_token();
+            let span = lexer.span();
+            spans.push((token.clone(), span.start, span.end, span.line, span.column));
+            if token == Token::EOF {
+                break;
+            }
+        }
+
+        assert_eq!(spans, vec![
+            (Token::Let, 0, 3, 1, 1),
+            (Token::Identifier("s".into()), 4, 5, 1, 5),
+            (Token::Operator("=".into()), 6, 7, 1, 7),
+            (Token::String("é".into()), 8, 12, 1, 9),
+            (Token::Symbol(';'), 12, 13, 1, 12),
+            (Token::Identifier("fim".into()), 25, 28, 3, 5),
+            (Token::EOF, 28, 28, 3, 8),
+        ]);
+    }
+
+    #[test]
+    fn malformed_literals_become_error_tokens() {
+        let unterminated = |message: &str| Token::Unterminated(message.into());
+        let invalid = |message: &str| Token::Error(message.into());
+        let cases = [
+            ("let s = \"sem fim;", unterminated("Unterminated string literal")),
+            ("`abc ${1}", unterminated("Unterminated template literal")),
+            ("`abc ${1", unterminated("Unterminated template interpolation")),
+            ("`abc ${\"x}`", unterminated("Unterminated string literal")),
+            (r#""\u{41"#, unterminated("Unterminated string literal")),
+            (r#""\q" + 1"#, invalid("Unknown escape sequence '\\q'")),
+            (r#"`\u{110000}${x}`"#, invalid("Invalid unicode escape '\\u{110000}'")),
+            ("let a = 1; /* /* */", unterminated("Unterminated block comment")),
+        ];
+
+        for (source, expected) in cases {
+            let errors: Vec<Token> = tokens(source).into_iter()
+                .filter(|token| matches!(token, Token::Error(_) | Token::Unterminated(_)))
+                .collect();
+            assert_eq!(errors, vec![expected], "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn numeric_literal_forms() {
+        let cases = [
+            ("42", 42.0),
+            ("3.25", 3.25),
+            ("1_000_000", 1_000_000.0),
+            ("1e6", 1e6),
+            ("2.5E-3", 2.5e-3),
+            ("1_0.0_1e+1_0", 10.01e10),
+            ("0xff", 255.0),
+            ("0XFF_FF", 65535.0),
+            ("0b1010", 10.0),
+            ("0b1111_0000", 240.0),
+            ("0o755", 493.0),
+            ("007", 7.0),
+        ];
+
+        for (source, expected) in cases {
+            assert_eq!(tokens(source), vec![Token::Number(expected)], "source: {}", source);
+        }
+
+        // O ponto só faz parte do número quando seguido de dígito
+        assert_eq!(tokens("1.x"), vec![
+            Token::Number(1.0), Token::Symbol('.'), Token::Identifier("x".into()),
+        ]);
+    }
+
+    #[test]
+    fn malformed_numeric_literals_are_errors() {
+        let cases = [
+            ("0x", "Invalid number literal '0x': expected hexadecimal digits"),
+            ("0b102", "Invalid number literal '0b102': invalid digit '2' in binary literal"),
+            ("0o8", "Invalid number literal '0o8': invalid digit '8' in octal literal"),
+            ("1e", "Invalid number literal '1e': expected digits in exponent"),
+            ("1e+", "Invalid number literal '1e+': expected digits in exponent"),
+            ("1__0", "Invalid number literal '1_': digit separator '_' must be between digits"),
+            ("100_", "Invalid number literal '100_': digit separator '_' must be between digits"),
+            ("0x_ff", "Invalid number literal '0x_': digit separator '_' must be between digits"),
+            ("12abc", "Invalid number literal '12a': invalid digit 'a' in decimal literal"),
+            ("0xfffffffffffffffff", "Invalid number literal '0xfffffffffffffffff': value is too large"),
+        ];
+
+        for (source, expected) in cases {
+            let mut lexer = Lexer::new(source);
+            assert_eq!(lexer.next_token(), Token::Error(expected.into()), "source: {}", source);
+            assert_eq!(lexer.span().start, 0, "source: {}", source);
+        }
+    }
+}
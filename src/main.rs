@@ -2,30 +2,303 @@ pub mod runtime;
 
 mod lexer;
 mod parser;
+mod environment;
 mod interpreter;
+mod error;
+mod span;
+mod repl;
+mod formatter;
+mod testing;
 
 use std::fs;
+use std::panic;
+use std::thread;
 use std::env;
+use std::io::{self, Read};
+use std::process::ExitCode;
+use crate::error::AdgError;
 use crate::lexer::Lexer;
 use crate::parser::Parser;
-use crate::interpreter::Interpreter;
+use crate::interpreter::{Interpreter, Value, STACK_SIZE};
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+const USAGE: &str = "\
+Uso: adg <comando> [argumentos]
 
-    if args.len() < 2 {
-        println!("Uso: adg arquivo.adg");
-        return;
+Comandos:
+  run <arquivo> [-- args...]    executa um arquivo (`adg arquivo.adg` também funciona)
+  check <arquivos...>           verifica a sintaxe, sem executar
+  eval <código>                 avalia o código e mostra o resultado
+  repl                          abre o REPL (o padrão, sem argumentos)
+  fmt [--check] <arquivos...>   formata os arquivos no lugar
+  test [caminhos...]            roda as funções test_* dos arquivos *_test.adg
+
+Use `-` no lugar de um arquivo (ou do código, no eval) para ler da entrada padrão.
+
+Opções:
+  -h, --help       mostra esta ajuda
+  -V, --version    mostra a versão
+
+Códigos de saída:
+  0  sucesso
+  1  erro de sintaxe ou de execução, teste falhando ou arquivo fora do formato
+  2  uso incorreto ou arquivo ilegível";
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Run { file: String, args: Vec<String> },
+    Check(Vec<String>),
+    Eval(String),
+    Repl,
+    Fmt { files: Vec<String>, check: bool },
+    Test(Vec<String>),
+    Help,
+    Version,
+}
+
+// O interpretador roda numa thread com pilha grande: é o limite de chamadas, e não a
+// pilha nativa, que interrompe uma recursão profunda
+fn main() -> ExitCode {
+    thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(start)
+        .expect("não foi possível criar a thread do interpretador")
+        .join()
+        .unwrap_or_else(|err| panic::resume_unwind(err))
+}
+
+fn start() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let command = match parse_args(&args) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("erro: {}\nUse `adg --help` para ver os comandos.", message);
+            return ExitCode::from(2);
+        }
+    };
+
+    match command {
+        Command::Run { file, args } => run(&file, args),
+        Command::Check(files) => check(&files),
+        Command::Eval(code) => eval(&code),
+        Command::Repl => repl::run(),
+        Command::Fmt { files, check } => fmt(&files, check),
+        Command::Test(paths) => testing::run(&paths),
+        Command::Help => {
+            println!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        Command::Version => {
+            println!("adg {}", env!("CARGO_PKG_VERSION"));
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let Some((first, rest)) = args.split_first() else {
+        return Ok(Command::Repl);
+    };
+
+    // Tudo depois do arquivo vai para o script; o `--` é opcional
+    let script_args = |rest: &[String]| -> Vec<String> {
+        rest.strip_prefix(&["--".to_string()]).unwrap_or(rest).to_vec()
+    };
+
+    match first.as_str() {
+        "-h" | "--help" | "help" => Ok(Command::Help),
+        "-V" | "--version" => Ok(Command::Version),
+        "run" => match rest.split_first() {
+            Some((file, args)) => Ok(Command::Run { file: file.clone(), args: script_args(args) }),
+            None => Err("`adg run` espera um arquivo".to_string()),
+        },
+        "check" if rest.is_empty() => Err("`adg check` espera ao menos um arquivo".to_string()),
+        "check" => Ok(Command::Check(rest.to_vec())),
+        "eval" => match rest {
+            [code] => Ok(Command::Eval(code.clone())),
+            _ => Err("`adg eval` espera exatamente um argumento com o código".to_string()),
+        },
+        "repl" if rest.is_empty() => Ok(Command::Repl),
+        "repl" => Err("`adg repl` não recebe argumentos".to_string()),
+        "fmt" => {
+            let check = rest.iter().any(|arg| arg == "--check");
+            let files: Vec<String> = rest.iter().filter(|arg| *arg != "--check").cloned().collect();
+            if files.is_empty() {
+                return Err("`adg fmt` espera ao menos um arquivo".to_string());
+            }
+            Ok(Command::Fmt { files, check })
+        }
+        "test" => Ok(Command::Test(rest.to_vec())),
+        option if option.starts_with('-') && option != "-" => Err(format!("opção desconhecida '{}'", option)),
+        file => Ok(Command::Run { file: file.to_string(), args: script_args(rest) }),
     }
+}
+
+// Lê o arquivo, ou a entrada padrão quando o caminho é `-`; devolve também o nome
+// usado nas mensagens de erro
+fn read_source(path: &str) -> Result<(String, String), ExitCode> {
+    let result = if path == "-" {
+        let mut code = String::new();
+        io::stdin().read_to_string(&mut code).map(|_| ("<stdin>".to_string(), code))
+    } else {
+        fs::read_to_string(path).map(|code| (path.to_string(), code))
+    };
+
+    result.map_err(|err| {
+        eprintln!("erro: não foi possível ler o arquivo {}: {}", path, err);
+        ExitCode::from(2)
+    })
+}
+
+fn report(errors: &[AdgError], path: &str, code: &str) {
+    let reports: Vec<String> = errors.iter().map(|err| err.report(path, code)).collect();
+    eprintln!("{}", reports.join("\n\n"));
+}
 
-    let filename = &args[1];
-    let code = fs::read_to_string(filename)
-        .unwrap_or_else(|_| panic!("Não foi possível ler o arquivo {}", filename));
+fn run(file: &str, args: Vec<String>) -> ExitCode {
+    let (path, code) = match read_source(file) {
+        Ok(source) => source,
+        Err(code) => return code,
+    };
 
-    let lexer = Lexer::new(&code);
-    let mut parser = Parser::new(lexer);
-    let ast = parser.parse();
+    // Erros de sintaxe são reportados todos juntos; na execução, o primeiro interrompe
+    let mut interpreter = script_interpreter(args);
+    match interpreter.run_file(&path, &code) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(errors) => {
+            // O erro pode ter vindo de um módulo importado
+            let reports: Vec<String> = errors.iter().map(|err| err.report_in(interpreter.sources())).collect();
+            eprintln!("{}", reports.join("\n\n"));
+            ExitCode::FAILURE
+        }
+    }
+}
 
+fn script_interpreter(args: Vec<String>) -> Interpreter {
     let mut interpreter = Interpreter::new();
-    interpreter.execute(ast);
+    interpreter.define_global("args", Value::array(args.into_iter().map(Value::String).collect()));
+    interpreter
+}
+
+fn check(files: &[String]) -> ExitCode {
+    let mut failed = false;
+    for file in files {
+        let (path, code) = match read_source(file) {
+            Ok(source) => source,
+            Err(code) => return code,
+        };
+        if let Err(errors) = Parser::new(Lexer::new(&code)).parse() {
+            report(&errors, &path, &code);
+            failed = true;
+        }
+    }
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn eval(code: &str) -> ExitCode {
+    let (path, code) = if code == "-" {
+        match read_source(code) {
+            Ok(source) => source,
+            Err(code) => return code,
+        }
+    } else {
+        ("<eval>".to_string(), code.to_string())
+    };
+
+    match repl::Repl::new().eval(&path, &code) {
+        Ok(output) => {
+            if let Some(output) = output {
+                println!("{}", output);
+            }
+            ExitCode::SUCCESS
+        }
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// Com `--check`, só lista os arquivos que mudariam
+fn fmt(files: &[String], check: bool) -> ExitCode {
+    let mut failed = false;
+    for file in files {
+        let (path, code) = match read_source(file) {
+            Ok(source) => source,
+            Err(code) => return code,
+        };
+
+        let formatted = match formatter::format(&code) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                report(&errors, &path, &code);
+                failed = true;
+                continue;
+            }
+        };
+
+        if file == "-" {
+            print!("{}", formatted);
+        } else if formatted != code {
+            if check {
+                println!("{}", path);
+                failed = true;
+            } else if let Err(err) = fs::write(file, &formatted) {
+                eprintln!("erro: não foi possível escrever o arquivo {}: {}", path, err);
+                return ExitCode::from(2);
+            }
+        }
+    }
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_args(&args)
+    }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_subcommands() {
+        assert_eq!(parse(&[]), Ok(Command::Repl));
+        assert_eq!(parse(&["--version"]), Ok(Command::Version));
+        assert_eq!(parse(&["-h"]), Ok(Command::Help));
+        assert_eq!(
+            parse(&["run", "a.adg", "--", "-v", "x"]),
+            Ok(Command::Run { file: "a.adg".into(), args: strings(&["-v", "x"]) }),
+        );
+        assert_eq!(
+            parse(&["a.adg", "x"]),
+            Ok(Command::Run { file: "a.adg".into(), args: strings(&["x"]) }),
+        );
+        assert_eq!(parse(&["-"]), Ok(Command::Run { file: "-".into(), args: vec![] }));
+        assert_eq!(parse(&["check", "a.adg", "b.adg"]), Ok(Command::Check(strings(&["a.adg", "b.adg"]))));
+        assert_eq!(parse(&["eval", "1 + 2"]), Ok(Command::Eval("1 + 2".into())));
+        assert_eq!(
+            parse(&["fmt", "--check", "a.adg"]),
+            Ok(Command::Fmt { files: strings(&["a.adg"]), check: true }),
+        );
+        assert_eq!(parse(&["test"]), Ok(Command::Test(vec![])));
+    }
+
+    #[test]
+    fn rejects_bad_usage() {
+        for args in [&["run"][..], &["check"], &["eval"], &["eval", "1", "2"], &["fmt", "--check"], &["repl", "x"], &["--bogus"]] {
+            assert!(parse(args).is_err(), "{:?} should be rejected", args);
+        }
+    }
+
+    #[test]
+    fn scripts_receive_their_arguments() {
+        let code = "assert_eq(args, [\"a\", \"b\"]);";
+        assert!(script_interpreter(strings(&["a", "b"])).run_file("<stdin>", code).is_ok());
+        assert!(script_interpreter(vec![]).run_file("<stdin>", "assert_eq(args, []);").is_ok());
+    }
 }
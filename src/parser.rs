@@ -1,352 +1,1872 @@
-use crate::lexer::{Lexer, Token};
+use std::rc::Rc;
+use crate::error::AdgError;
+use crate::lexer::{Lexer, TemplatePart, Token};
+use crate::span::Span;
 
 #[derive(Debug, Clone)]
 pub enum Literal {
     Number(f64),
     Array(Vec<Expr>),
+    Object(Vec<(String, Expr)>),
     String(String),
     Boolean(bool),
     Null,
 }
 
+/// Expressão junto com o trecho do código-fonte de onde ela veio.
 #[derive(Debug, Clone)]
-pub enum Expr {
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExprKind {
     Index(Box<Expr>, Box<Expr>),
     Literal(Literal),
     Variable(String),
+    // Template string: os pedaços são concatenados com `as_string`
+    Template(Vec<Expr>),
+    Member(Box<Expr>, String),
+    Unary(String, Box<Expr>),
     BinaryOp(Box<Expr>, String, Box<Expr>),
-    Call(String, Vec<Expr>),
-    Assignment(String, Box<Expr>),
+    Call(Box<Expr>, Vec<Expr>),
+    // O corpo é compartilhado com as funções criadas a partir dele, sem cópia
+    Function { params: Vec<String>, body: Rc<[Stmt]> },
+    Assignment(Box<Expr>, Box<Expr>),
+    CompoundAssignment(Box<Expr>, String, Box<Expr>),
+    Update { target: Box<Expr>, op: String, prefix: bool },
+    Match { subject: Box<Expr>, arms: Vec<MatchArm> },
+    // Trecho com erro de sintaxe, mantido para que o resto da árvore seja aproveitado
+    Error,
 }
 
+/// Statement junto com o trecho do código-fonte de onde ele veio.
 #[derive(Debug, Clone)]
-pub enum Stmt {
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub enum StmtKind {
     VarDecl { name: String, value: Expr, is_const: bool },
     Assignment(String, Box<Expr>),
     Expression(Expr),
     If { condition: Expr, then_branch: Vec<Stmt>, else_branch: Option<Vec<Stmt>> },
     Looping { condition: Expr, body: Vec<Stmt> },
+    DoWhile { body: Vec<Stmt>, condition: Expr },
     For { init: Box<Stmt>, condition: Expr, update: Expr, body: Vec<Stmt> },
     Break,
     Continue,
-    Function { name: String, params: Vec<String>, body: Vec<Stmt> },
+    Function { name: String, params: Vec<String>, body: Rc<[Stmt]>, doc: Option<String> },
     Return(Option<Expr>),
+    Throw(Expr),
+    Try {
+        body: Vec<Stmt>,
+        catch_name: Option<String>,
+        catch_body: Option<Vec<Stmt>>,
+        finally_body: Option<Vec<Stmt>>,
+    },
+    Switch { subject: Expr, cases: Vec<SwitchCase> },
     Block(Vec<Stmt>),
+    Import { clause: ImportClause, source: String },
+    // `struct` (ou `class`): campos na ordem de declaração e métodos
+    Struct { name: String, fields: Vec<Field>, methods: Vec<Method> },
+    Enum { name: String, variants: Vec<Variant> },
+    // `match` no início de um statement: `return`, `break` e `continue` valem nos braços
+    Match { subject: Expr, arms: Vec<MatchArm> },
+    // `export` seguido de uma declaração (`let`, `const`, `fn`, `struct` ou `enum`)
+    Export(Box<Stmt>),
+    // Statement com erro de sintaxe, pulado até o próximo ponto de sincronização
+    Error,
+}
+
+/// Um braço do `switch`; `test` é `None` para o `default`.
+#[derive(Debug, Clone)]
+pub struct SwitchCase {
+    pub test: Option<Expr>,
+    pub body: Vec<Stmt>,
+}
+
+/// Campo de um `struct`, com o valor padrão opcional (`y = 0`).
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    pub default: Option<Expr>,
+}
+
+/// Método de um `struct`; o `init` é o construtor.
+#[derive(Debug, Clone)]
+pub struct Method {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Rc<[Stmt]>,
+}
+
+/// Variante de um `enum`, com os nomes dos valores que carrega (`Rect(w, h)`).
+#[derive(Debug, Clone)]
+pub struct Variant {
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
+/// Um braço do `match`: padrão, guarda opcional (`if ...`) e corpo. Um corpo
+/// escrito como expressão vira um único statement de expressão.
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Vec<Stmt>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    // `_`
+    Wildcard,
+    Binding(String),
+    // Número, string ou booleano
+    Literal(Literal),
+    // `Shape.Rect(w, h)`; sem parênteses (`Shape.Rect`), o conteúdo não é conferido
+    Variant { enum_name: String, variant: String, args: Option<Vec<Pattern>> },
+    // `[a, b, ...resto]`; `..._` aceita o resto sem guardá-lo
+    Array { items: Vec<Pattern>, rest: Option<String> },
+    // `{ x, y: 0 }`: as chaves precisam existir
+    Object(Vec<(String, Pattern)>),
+}
+
+impl Pattern {
+    /// Variáveis que o padrão declara, na ordem em que aparecem.
+    pub fn bindings(&self) -> Vec<&str> {
+        match self {
+            Pattern::Wildcard | Pattern::Literal(_) => vec![],
+            Pattern::Binding(name) => vec![name],
+            Pattern::Variant { args, .. } => args.iter().flatten().flat_map(Pattern::bindings).collect(),
+            Pattern::Array { items, rest } => items.iter()
+                .flat_map(Pattern::bindings)
+                .chain(rest.as_deref().filter(|name| *name != "_"))
+                .collect(),
+            Pattern::Object(entries) => entries.iter().flat_map(|(_, pattern)| pattern.bindings()).collect(),
+        }
+    }
+
+    /// Casa com qualquer valor, sem precisar olhar para ele.
+    pub fn is_irrefutable(&self) -> bool {
+        matches!(self, Pattern::Wildcard | Pattern::Binding(_))
+    }
+}
+
+/// O que um `import` traz: o módulo inteiro (`import util from ...`) ou nomes
+/// avulsos, cada um com o nome local (`import { a, b as c } from ...`).
+#[derive(Debug, Clone)]
+pub enum ImportClause {
+    Module(String),
+    Names(Vec<(String, String)>),
+}
+
+impl Expr {
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        Expr { kind, span }
+    }
+}
+
+impl Stmt {
+    pub fn new(kind: StmtKind, span: Span) -> Self {
+        Stmt { kind, span }
+    }
+}
+
+type ParseResult<T> = Result<T, AdgError>;
+
+/// Onde `break` e `continue` são permitidos; funções começam um contexto novo.
+/// `import`/`export` só valem no topo do arquivo, e `return` não vale nos
+/// braços de um `match` usado como expressão.
+#[derive(Debug, Clone, Copy, Default)]
+struct Context {
+    in_loop: bool,
+    in_switch: bool,
+    top_level: bool,
+    in_match_expression: bool,
 }
 
 pub struct Parser {
     lexer: Lexer,
     current: Token,
+    // Trechos do token atual e do último token consumido
+    span: Span,
+    previous: Span,
+    // Comentário `///` que precede o token atual
+    current_doc: Option<String>,
+    context: Context,
+    // Erros já registrados, dos quais o parser se recuperou
+    errors: Vec<AdgError>,
+    // Um literal ou comentário sem fim consumiu o resto do arquivo
+    truncated: bool,
 }
 
 impl Parser {
     pub fn new(mut lexer: Lexer) -> Self {
         let current = lexer.next_token();
-        Parser { lexer, current }
+        let span = lexer.span();
+        let current_doc = lexer.take_doc();
+        let truncated = matches!(current, Token::Unterminated(_));
+        Parser {
+            lexer,
+            current,
+            span,
+            previous: span,
+            current_doc,
+            context: Context::default(),
+            errors: vec![],
+            truncated,
+        }
     }
 
     fn advance(&mut self) {
+        self.previous = self.span;
         self.current = self.lexer.next_token();
+        self.span = self.lexer.span();
+        self.current_doc = self.lexer.take_doc();
+        self.truncated |= matches!(self.current, Token::Unterminated(_));
+    }
+
+    // Olha o token seguinte ao atual sem consumi-lo
+    fn peek(&mut self) -> Token {
+        let checkpoint = self.lexer.checkpoint();
+        let token = self.lexer.next_token();
+        self.lexer.rewind(checkpoint);
+        token
     }
 
-    fn expect(&mut self, expected: &Token) {
+    // Erro apontando para o token atual
+    fn error(&self, message: impl Into<String>) -> AdgError {
+        // Um literal malformado é a causa real de qualquer erro encontrado sobre ele
+        match &self.current {
+            Token::Error(lex_message) | Token::Unterminated(lex_message) => AdgError::lex(lex_message.clone(), self.span),
+            _ => AdgError::parse(message, self.span),
+        }
+    }
+
+    // Trecho que vai de `start` até o último token consumido
+    fn span_from(&self, start: Span) -> Span {
+        start.to(self.previous)
+    }
+
+    fn expect(&mut self, expected: &Token) -> ParseResult<()> {
         if &self.current != expected {
-            panic!("Expected {:?}, but found {:?}", expected, self.current);
+            return Err(self.error(format!("Expected {:?}, but found {:?}", expected, self.current)));
         }
         self.advance();
+        Ok(())
     }
 
-    fn expect_operator(&mut self, expected: &str) {
+    fn expect_identifier(&mut self, message: &str) -> ParseResult<String> {
         match &self.current {
-            Token::Operator(op) if op == expected => self.advance(),
-            other => panic!("Expected operator '{}', but found {:?}", expected, other),
+            Token::Identifier(name) => {
+                let name = name.clone();
+                self.advance();
+                Ok(name)
+            }
+            _ => Err(self.error(message)),
         }
     }
 
-    pub fn parse(&mut self) -> Vec<Stmt> {
+    fn expect_operator(&mut self, expected: &str) -> ParseResult<()> {
+        match &self.current {
+            Token::Operator(op) if op == expected => {
+                self.advance();
+                Ok(())
+            }
+            other => Err(self.error(format!("Expected operator '{}', but found {:?}", expected, other))),
+        }
+    }
+
+    fn skip_semicolon(&mut self) {
+        if self.current == Token::Symbol(';') {
+            self.advance();
+        }
+    }
+
+    // Analisa `f` num outro contexto de laço/switch, restaurando o anterior depois
+    fn with_context<T>(&mut self, context: Context, f: impl FnOnce(&mut Self) -> T) -> T {
+        let previous = std::mem::replace(&mut self.context, context);
+        let result = f(self);
+        self.context = previous;
+        result
+    }
+
+    /// Analisa o arquivo inteiro; se houver erros de sintaxe, devolve todos eles.
+    pub fn parse(&mut self) -> Result<Vec<Stmt>, Vec<AdgError>> {
+        let (stmts, errors) = self.parse_partial();
+        if errors.is_empty() { Ok(stmts) } else { Err(errors) }
+    }
+
+    /// Analisa o arquivo inteiro mesmo com erros: devolve a árvore parcial, com nós
+    /// `Error` no lugar dos trechos inválidos, e os erros na ordem em que aparecem.
+    pub fn parse_partial(&mut self) -> (Vec<Stmt>, Vec<AdgError>) {
+        self.context.top_level = true;
         let mut stmts = vec![];
         while self.current != Token::EOF {
-            stmts.push(self.statement());
+            stmts.push(self.recovering_statement());
         }
-        stmts
+        let mut errors = std::mem::take(&mut self.errors);
+        // Depois de um literal sem fim, o que ficou por fechar no fim do arquivo (`;`,
+        // `}`, `)`) não é um erro à parte
+        if self.truncated {
+            let eof = self.span.start;
+            errors.retain(|err| err.span.is_none_or(|span| span.start < eof));
+        }
+        errors.sort_by_key(|err| err.span.map(|span| span.start));
+        (stmts, errors)
     }
 
-    fn statement(&mut self) -> Stmt {
-        match self.current {
-            Token::Let | Token::Const => self.variable_decl(),
-            Token::If => self.if_statement(),
-            Token::Looping => self.looping_statement(),
-            Token::For => self.for_statement(),
-            Token::Break => { self.advance(); Stmt::Break },
-            Token::Continue => { self.advance(); Stmt::Continue },
-            Token::Fn => self.function_statement(),
-            Token::Return => self.return_statement(),
-            Token::Symbol('{') => self.block(),
-            _ => self.expression_statement(),
+    // Statement que não aborta a análise: em caso de erro, registra-o, pula até um
+    // ponto seguro e deixa um nó `Error` no lugar
+    fn recovering_statement(&mut self) -> Stmt {
+        let start = self.span;
+        match self.statement() {
+            Ok(stmt) => stmt,
+            Err(err) => {
+                self.errors.push(err);
+                // Garante progresso quando o erro está no primeiro token
+                if self.span.start == start.start && self.current != Token::EOF {
+                    self.advance();
+                }
+                self.synchronize();
+                Stmt::new(StmtKind::Error, self.span_from(start))
+            }
         }
     }
 
-    fn variable_decl(&mut self) -> Stmt {
+    // Pula tokens até um ponto onde a análise pode recomeçar: logo após um `;` ou um
+    // bloco `{ ... }`, antes de um `}` que feche o bloco atual, ou antes de uma
+    // palavra-chave que inicia um statement
+    fn synchronize(&mut self) {
+        let mut depth = 0;
+        loop {
+            match self.current {
+                Token::EOF => return,
+                Token::Symbol('}') if depth == 0 => return,
+                Token::Symbol(';') if depth == 0 => {
+                    self.advance();
+                    return;
+                }
+                Token::Symbol('{') => depth += 1,
+                Token::Symbol('}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.advance();
+                        return;
+                    }
+                }
+                Token::Let | Token::Const | Token::Fn | Token::If | Token::Looping | Token::While
+                | Token::Do | Token::For | Token::Return | Token::Throw | Token::Try | Token::Switch
+                | Token::Case | Token::Default | Token::Break | Token::Continue | Token::Import
+                | Token::Export | Token::Struct | Token::Class | Token::Enum | Token::Match
+                    if depth == 0 => return,
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
+    fn statement(&mut self) -> ParseResult<Stmt> {
+        let start = self.span;
+        // Os statements dentro deste já não estão no topo do arquivo
+        let top_level = self.context.top_level;
+        let nested = Context { top_level: false, ..self.context };
+        let kind = self.with_context(nested, |this| this.statement_kind(top_level))?;
+        Ok(Stmt::new(kind, self.span_from(start)))
+    }
+
+    fn statement_kind(&mut self, top_level: bool) -> ParseResult<StmtKind> {
+        let kind = match self.current {
+            Token::Let | Token::Const => self.variable_decl()?,
+            Token::If => self.if_statement()?,
+            // `while` é apenas outro nome para `looping`
+            Token::Looping | Token::While => self.looping_statement()?,
+            Token::Do => self.do_while_statement()?,
+            Token::For => self.for_statement()?,
+            Token::Break => {
+                if !self.context.in_loop && !self.context.in_switch {
+                    self.errors.push(self.error("'break' outside of a loop or switch"));
+                }
+                self.advance();
+                self.skip_semicolon();
+                StmtKind::Break
+            }
+            Token::Continue => {
+                if !self.context.in_loop {
+                    self.errors.push(self.error("'continue' outside of a loop"));
+                }
+                self.advance();
+                self.skip_semicolon();
+                StmtKind::Continue
+            }
+            // `fn nome(...)` declara; `fn (...)` é uma expressão de função
+            Token::Fn => match self.peek() {
+                Token::Identifier(_) => self.function_statement()?,
+                _ => self.expression_statement()?,
+            },
+            Token::Return => self.return_statement()?,
+            Token::Throw => self.throw_statement()?,
+            Token::Try => self.try_statement()?,
+            Token::Switch => self.switch_statement()?,
+            // `class` é apenas outro nome para `struct`
+            Token::Struct | Token::Class => self.struct_statement()?,
+            Token::Enum => self.enum_statement()?,
+            Token::Match => {
+                let (subject, arms) = self.match_arms(false)?;
+                self.skip_semicolon();
+                StmtKind::Match { subject, arms }
+            }
+            Token::Symbol('{') => StmtKind::Block(self.block_body()?),
+            Token::Import | Token::Export => {
+                let is_import = self.current == Token::Import;
+                if !top_level {
+                    let keyword = if is_import { "import" } else { "export" };
+                    self.errors.push(self.error(format!("'{}' is only allowed at the top level of a file", keyword)));
+                }
+                if is_import { self.import_statement()? } else { self.export_statement()? }
+            }
+            _ => self.expression_statement()?,
+        };
+        Ok(kind)
+    }
+
+    // Corpo de if/laço/função: um bloco ou um único statement
+    fn branch(&mut self) -> ParseResult<Vec<Stmt>> {
+        Ok(match self.statement()? {
+            Stmt { kind: StmtKind::Block(stmts), .. } => stmts,
+            stmt => vec![stmt],
+        })
+    }
+
+    fn loop_body(&mut self) -> ParseResult<Vec<Stmt>> {
+        let context = Context { in_loop: true, ..self.context };
+        self.with_context(context, |this| this.branch())
+    }
+
+    fn variable_decl(&mut self) -> ParseResult<StmtKind> {
         let is_const = matches!(self.current, Token::Const);
         self.advance();
 
         let name = match &self.current {
             Token::Identifier(name) => name.clone(),
-            _ => panic!("Expected identifier after let/const"),
+            _ => return Err(self.error("Expected identifier after let/const")),
         };
         self.advance();
 
-        self.expect_operator("=");
-        let value = self.expression();
+        self.expect_operator("=")?;
+        let value = self.expression()?;
 
-        self.expect(&Token::Symbol(';'));
-        Stmt::VarDecl { name, value, is_const }
+        self.expect(&Token::Symbol(';'))?;
+        Ok(StmtKind::VarDecl { name, value, is_const })
     }
 
-    fn if_statement(&mut self) -> Stmt {
+    fn if_statement(&mut self) -> ParseResult<StmtKind> {
         self.advance();
-        self.expect(&Token::Symbol('('));
-        let condition = self.expression();
-        self.expect(&Token::Symbol(')'));
+        self.expect(&Token::Symbol('('))?;
+        let condition = self.expression()?;
+        self.expect(&Token::Symbol(')'))?;
 
-        let then_branch = match self.statement() {
-            Stmt::Block(stmts) => stmts,
-            stmt => vec![stmt],
-        };
+        let then_branch = self.branch()?;
 
         let else_branch = if self.current == Token::Else {
             self.advance();
-            let branch = match self.statement() {
-                Stmt::Block(stmts) => stmts,
-                stmt => vec![stmt],
-            };
-            Some(branch)
+            Some(self.branch()?)
         } else {
             None
         };
 
-        Stmt::If { condition, then_branch, else_branch }
+        Ok(StmtKind::If { condition, then_branch, else_branch })
     }
 
-    fn looping_statement(&mut self) -> Stmt {
+    fn looping_statement(&mut self) -> ParseResult<StmtKind> {
         self.advance();
-        self.expect(&Token::Symbol('('));
-        let condition = self.expression();
-        self.expect(&Token::Symbol(')'));
+        self.expect(&Token::Symbol('('))?;
+        let condition = self.expression()?;
+        self.expect(&Token::Symbol(')'))?;
 
-        let body = match self.statement() {
-            Stmt::Block(stmts) => stmts,
-            stmt => vec![stmt],
-        };
+        let body = self.loop_body()?;
 
-        Stmt::Looping { condition, body }
+        Ok(StmtKind::Looping { condition, body })
     }
 
-    fn for_statement(&mut self) -> Stmt {
+    fn do_while_statement(&mut self) -> ParseResult<StmtKind> {
         self.advance();
-        self.expect(&Token::Symbol('('));
+        let body = self.loop_body()?;
 
-        let init = Box::new(self.variable_decl());
-        let condition = self.expression();
-        self.expect(&Token::Symbol(';'));
-        let update = self.expression();
+        self.expect(&Token::While)?;
+        self.expect(&Token::Symbol('('))?;
+        let condition = self.expression()?;
+        self.expect(&Token::Symbol(')'))?;
+        self.skip_semicolon();
 
-        self.expect(&Token::Symbol(')'));
-        let body = match self.statement() {
-            Stmt::Block(stmts) => stmts,
-            stmt => vec![stmt],
-        };
+        Ok(StmtKind::DoWhile { body, condition })
+    }
+
+    fn for_statement(&mut self) -> ParseResult<StmtKind> {
+        self.advance();
+        self.expect(&Token::Symbol('('))?;
+
+        let start = self.span;
+        let init = self.variable_decl()?;
+        let init = Box::new(Stmt::new(init, self.span_from(start)));
+        let condition = self.expression()?;
+        self.expect(&Token::Symbol(';'))?;
+        let update = self.expression()?;
 
-        Stmt::For { init, condition, update, body }
+        self.expect(&Token::Symbol(')'))?;
+        let body = self.loop_body()?;
+
+        Ok(StmtKind::For { init, condition, update, body })
     }
 
-    fn function_statement(&mut self) -> Stmt {
+    fn function_statement(&mut self) -> ParseResult<StmtKind> {
+        let doc = self.current_doc.take();
         self.advance();
 
         let name = match &self.current {
             Token::Identifier(s) => s.clone(),
-            _ => panic!("Expected function name"),
+            _ => return Err(self.error("Expected function name")),
+        };
+        self.advance();
+
+        let params = self.parameters()?;
+        let body = self.with_context(Context::default(), |this| this.branch())?;
+
+        Ok(StmtKind::Function { name, params, body: body.into(), doc })
+    }
+
+    // `struct Nome { x, y = 0, fn metodo(...) { ... } }`
+    fn struct_statement(&mut self) -> ParseResult<StmtKind> {
+        self.advance();
+        let name = self.expect_identifier("Expected struct name")?;
+        self.expect(&Token::Symbol('{'))?;
+
+        let mut fields: Vec<Field> = vec![];
+        let mut methods: Vec<Method> = vec![];
+        while self.current != Token::Symbol('}') && self.current != Token::EOF {
+            let member_span = self.span;
+            let member = if self.current == Token::Fn {
+                self.advance();
+                let member = self.expect_identifier("Expected method name")?;
+                let params = self.parameters()?;
+                let body = self.with_context(Context::default(), |this| this.block_body())?;
+                methods.push(Method { name: member.clone(), params, body: body.into() });
+                member
+            } else {
+                let member = self.expect_identifier("Expected field or method in struct")?;
+                let default = if self.current == Token::Operator("=".to_string()) {
+                    self.advance();
+                    Some(self.expression()?)
+                } else {
+                    None
+                };
+                // Campos são separados por `,` ou `;`
+                if matches!(self.current, Token::Symbol(',' | ';')) {
+                    self.advance();
+                }
+                fields.push(Field { name: member.clone(), default });
+                member
+            };
+
+            let count = fields.iter().filter(|f| f.name == member).count()
+                + methods.iter().filter(|m| m.name == member).count();
+            if count > 1 {
+                self.errors.push(AdgError::parse(format!("Duplicate member '{}' in struct {}", member, name), member_span));
+            }
+        }
+        self.expect(&Token::Symbol('}'))?;
+
+        Ok(StmtKind::Struct { name, fields, methods })
+    }
+
+    // `enum Nome { A, B(x, y) }`
+    fn enum_statement(&mut self) -> ParseResult<StmtKind> {
+        self.advance();
+        let name = self.expect_identifier("Expected enum name")?;
+        self.expect(&Token::Symbol('{'))?;
+
+        let mut variants: Vec<Variant> = vec![];
+        while self.current != Token::Symbol('}') && self.current != Token::EOF {
+            let variant_span = self.span;
+            let variant = self.expect_identifier("Expected variant name")?;
+            let fields = if self.current == Token::Symbol('(') { self.parameters()? } else { vec![] };
+            if variants.iter().any(|v| v.name == variant) {
+                self.errors.push(AdgError::parse(format!("Duplicate variant '{}' in enum {}", variant, name), variant_span));
+            }
+            variants.push(Variant { name: variant, fields });
+
+            if self.current == Token::Symbol(',') {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        self.expect(&Token::Symbol('}'))?;
+
+        Ok(StmtKind::Enum { name, variants })
+    }
+
+    // `match (valor) { padrão if (guarda) => corpo, ... }`; como expressão, os corpos
+    // não podem desviar o fluxo para fora do `match`
+    fn match_arms(&mut self, expression: bool) -> ParseResult<(Expr, Vec<MatchArm>)> {
+        self.advance();
+        self.expect(&Token::Symbol('('))?;
+        let subject = self.expression()?;
+        self.expect(&Token::Symbol(')'))?;
+        self.expect(&Token::Symbol('{'))?;
+
+        let context = if expression {
+            Context { in_match_expression: true, ..Context::default() }
+        } else {
+            self.context
+        };
+        let mut arms = vec![];
+        while self.current != Token::Symbol('}') && self.current != Token::EOF {
+            let pattern_span = self.span;
+            let pattern = self.pattern()?;
+            let bindings = pattern.bindings();
+            if let Some(name) = bindings.iter().enumerate().find_map(|(i, b)| bindings[..i].contains(b).then_some(b)) {
+                self.errors.push(AdgError::parse(format!("Duplicate binding '{}' in pattern", name), pattern_span));
+            }
+
+            // A guarda vai entre parênteses, como no `if`; sem eles, `x => ...` seria uma arrow function
+            let guard = if self.current == Token::If {
+                self.advance();
+                self.expect(&Token::Symbol('('))?;
+                let guard = self.expression()?;
+                self.expect(&Token::Symbol(')'))?;
+                Some(guard)
+            } else {
+                None
+            };
+            self.expect_operator("=>")?;
+
+            let body = self.with_context(context, |this| {
+                if this.current == Token::Symbol('{') {
+                    this.block_body()
+                } else {
+                    let value = this.expression()?;
+                    let span = value.span;
+                    Ok(vec![Stmt::new(StmtKind::Expression(value), span)])
+                }
+            })?;
+            arms.push(MatchArm { pattern, guard, body });
+
+            if self.current == Token::Symbol(',') {
+                self.advance();
+            }
+        }
+        self.expect(&Token::Symbol('}'))?;
+
+        Ok((subject, arms))
+    }
+
+    fn pattern(&mut self) -> ParseResult<Pattern> {
+        let pattern = match self.current.clone() {
+            Token::Number(n) => Pattern::Literal(Literal::Number(n)),
+            Token::Operator(op) if op == "-" => {
+                self.advance();
+                match self.current {
+                    Token::Number(n) => Pattern::Literal(Literal::Number(-n)),
+                    _ => return Err(self.error(format!("Expected number after '-' in pattern, but found {:?}", self.current))),
+                }
+            }
+            Token::String(s) => Pattern::Literal(Literal::String(s)),
+            Token::Boolean(b) => Pattern::Literal(Literal::Boolean(b)),
+            Token::Null => Pattern::Literal(Literal::Null),
+            Token::Symbol('[') => return self.array_pattern(),
+            Token::Symbol('{') => return self.object_pattern(),
+            Token::Identifier(name) => {
+                self.advance();
+                if self.current != Token::Symbol('.') {
+                    return Ok(if name == "_" { Pattern::Wildcard } else { Pattern::Binding(name) });
+                }
+                self.advance();
+                let variant = self.expect_identifier("Expected variant name after '.'")?;
+                let args = if self.current == Token::Symbol('(') {
+                    self.advance();
+                    let mut args = vec![];
+                    while self.current != Token::Symbol(')') {
+                        args.push(self.pattern()?);
+                        if self.current == Token::Symbol(',') {
+                            self.advance();
+                        } else {
+                            break;
+                        }
+                    }
+                    self.expect(&Token::Symbol(')'))?;
+                    Some(args)
+                } else {
+                    None
+                };
+                return Ok(Pattern::Variant { enum_name: name, variant, args });
+            }
+            other => return Err(self.error(format!("Expected pattern, but found {:?}", other))),
         };
         self.advance();
+        Ok(pattern)
+    }
 
-        self.expect(&Token::Symbol('('));
+    fn array_pattern(&mut self) -> ParseResult<Pattern> {
+        self.expect(&Token::Symbol('['))?;
+        let mut items = vec![];
+        let mut rest = None;
+        while self.current != Token::Symbol(']') {
+            // `...resto` fecha o padrão
+            if self.current == Token::Symbol('.') {
+                for _ in 0..3 {
+                    self.expect(&Token::Symbol('.'))?;
+                }
+                let name = self.expect_identifier("Expected name after '...' in pattern")?;
+                rest = Some(name);
+                break;
+            }
+            items.push(self.pattern()?);
+            if self.current == Token::Symbol(',') {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        self.expect(&Token::Symbol(']'))?;
+        Ok(Pattern::Array { items, rest })
+    }
+
+    fn object_pattern(&mut self) -> ParseResult<Pattern> {
+        self.expect(&Token::Symbol('{'))?;
+        let mut entries = vec![];
+        while self.current != Token::Symbol('}') {
+            let (key, shorthand) = match (&self.current, self.property_name()) {
+                (Token::String(s), _) => (s.clone(), false),
+                (token, Some(name)) => (name, matches!(token, Token::Identifier(_))),
+                (other, None) => return Err(self.error(format!("Expected property name in pattern, but found {:?}", other))),
+            };
+            self.advance();
+
+            // `{ x }` é um atalho para `{ x: x }`
+            let pattern = if shorthand && matches!(self.current, Token::Symbol(',' | '}')) {
+                Pattern::Binding(key.clone())
+            } else {
+                self.expect(&Token::Symbol(':'))?;
+                self.pattern()?
+            };
+            entries.push((key, pattern));
+
+            if self.current == Token::Symbol(',') {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        self.expect(&Token::Symbol('}'))?;
+        Ok(Pattern::Object(entries))
+    }
+
+    fn parameters(&mut self) -> ParseResult<Vec<String>> {
+        self.expect(&Token::Symbol('('))?;
         let mut params = vec![];
         while self.current != Token::Symbol(')') {
             if let Token::Identifier(s) = &self.current {
                 params.push(s.clone());
                 self.advance();
-                if self.current == Token::Symbol(',') {
-                    self.advance();
-                }
+                self.list_separator(')', "parameter list")?;
             } else {
-                panic!("Expected parameter name");
+                return Err(self.error("Expected parameter name"));
             }
         }
-        self.expect(&Token::Symbol(')'));
+        self.expect(&Token::Symbol(')'))?;
+        Ok(params)
+    }
 
-        let body = match self.statement() {
-            Stmt::Block(stmts) => stmts,
-            stmt => vec![stmt],
+    // `fn (a, b) { ... }`
+    fn function_expr(&mut self) -> ParseResult<ExprKind> {
+        self.advance();
+        let params = self.parameters()?;
+        let body = self.with_context(Context::default(), |this| this.block_body())?;
+        Ok(ExprKind::Function { params, body: body.into() })
+    }
+
+    // Tenta ler `(a, b) =>`; se não for uma arrow function, volta ao ponto de partida
+    fn arrow_params(&mut self) -> Option<Vec<String>> {
+        let saved = (self.current.clone(), self.span, self.previous, self.lexer.checkpoint());
+
+        self.advance();
+        let mut params = vec![];
+        while let Token::Identifier(name) = &self.current {
+            params.push(name.clone());
+            self.advance();
+            if self.current != Token::Symbol(',') {
+                break;
+            }
+            self.advance();
+        }
+
+        if self.current == Token::Symbol(')') {
+            self.advance();
+            if self.current == Token::Operator("=>".to_string()) {
+                self.advance();
+                return Some(params);
+            }
+        }
+
+        (self.current, self.span, self.previous) = (saved.0, saved.1, saved.2);
+        self.lexer.rewind(saved.3);
+        None
+    }
+
+    // Corpo de arrow function: bloco, ou uma expressão que vira o retorno
+    fn arrow_body(&mut self, params: Vec<String>, start: Span) -> ParseResult<Expr> {
+        let body = self.with_context(Context::default(), |this| {
+            if this.current == Token::Symbol('{') {
+                this.block_body()
+            } else {
+                let value = this.assignment()?;
+                let span = value.span;
+                Ok(vec![Stmt::new(StmtKind::Return(Some(value)), span)])
+            }
+        })?;
+        Ok(Expr::new(ExprKind::Function { params, body: body.into() }, self.span_from(start)))
+    }
+
+    // `import nome from "arquivo"` ou `import { a, b as c } from "arquivo"`
+    fn import_statement(&mut self) -> ParseResult<StmtKind> {
+        self.advance();
+
+        let clause = match self.current {
+            Token::Identifier(_) => ImportClause::Module(self.expect_identifier("Expected module name")?),
+            Token::Symbol('{') => {
+                self.advance();
+                let mut names = vec![];
+                while self.current != Token::Symbol('}') {
+                    let name = self.expect_identifier("Expected name to import")?;
+                    let local = if self.current == Token::Identifier("as".to_string()) {
+                        self.advance();
+                        self.expect_identifier("Expected name after 'as'")?
+                    } else {
+                        name.clone()
+                    };
+                    names.push((name, local));
+                    if self.current == Token::Symbol(',') {
+                        self.advance();
+                    } else {
+                        break;
+                    }
+                }
+                self.expect(&Token::Symbol('}'))?;
+                ImportClause::Names(names)
+            }
+            _ => return Err(self.error(format!("Expected module name or '{{' after import, but found {:?}", self.current))),
         };
 
-        Stmt::Function { name, params, body }
+        // `from` e `as` não são palavras reservadas
+        if self.current != Token::Identifier("from".to_string()) {
+            return Err(self.error(format!("Expected 'from', but found {:?}", self.current)));
+        }
+        self.advance();
+
+        let source = match &self.current {
+            Token::String(source) => source.clone(),
+            other => return Err(self.error(format!("Expected module path string, but found {:?}", other))),
+        };
+        self.advance();
+        self.expect(&Token::Symbol(';'))?;
+
+        Ok(StmtKind::Import { clause, source })
     }
 
-    fn return_statement(&mut self) -> Stmt {
+    fn export_statement(&mut self) -> ParseResult<StmtKind> {
+        // A documentação escrita antes do `export` é da função exportada
+        let doc = self.current_doc.take();
+        self.advance();
+        if self.current_doc.is_none() {
+            self.current_doc = doc;
+        }
+
+        let start = self.span;
+        let kind = match self.current {
+            Token::Let | Token::Const => self.variable_decl()?,
+            Token::Fn => self.function_statement()?,
+            Token::Struct | Token::Class => self.struct_statement()?,
+            Token::Enum => self.enum_statement()?,
+            _ => return Err(self.error(format!(
+                "Expected 'let', 'const', 'fn', 'struct' or 'enum' after 'export', but found {:?}",
+                self.current
+            ))),
+        };
+        Ok(StmtKind::Export(Box::new(Stmt::new(kind, self.span_from(start)))))
+    }
+
+    fn return_statement(&mut self) -> ParseResult<StmtKind> {
+        if self.context.in_match_expression {
+            self.errors.push(self.error("'return' inside a match expression"));
+        }
         self.advance();
         if self.current == Token::Symbol(';') {
             self.advance();
-            Stmt::Return(None)
+            Ok(StmtKind::Return(None))
+        } else {
+            let expr = self.expression()?;
+            self.expect(&Token::Symbol(';'))?;
+            Ok(StmtKind::Return(Some(expr)))
+        }
+    }
+
+    fn throw_statement(&mut self) -> ParseResult<StmtKind> {
+        self.advance();
+        let expr = self.expression()?;
+        self.expect(&Token::Symbol(';'))?;
+        Ok(StmtKind::Throw(expr))
+    }
+
+    fn try_statement(&mut self) -> ParseResult<StmtKind> {
+        self.advance();
+        let body = self.block_body()?;
+
+        let (catch_name, catch_body) = if self.current == Token::Catch {
+            self.advance();
+            self.expect(&Token::Symbol('('))?;
+            let name = match &self.current {
+                Token::Identifier(s) => s.clone(),
+                other => return Err(self.error(format!("Expected identifier in catch clause, but found {:?}", other))),
+            };
+            self.advance();
+            self.expect(&Token::Symbol(')'))?;
+            (Some(name), Some(self.block_body()?))
         } else {
-            let expr = self.expression();
-            self.expect(&Token::Symbol(';'));
-            Stmt::Return(Some(expr))
+            (None, None)
+        };
+
+        let finally_body = if self.current == Token::Finally {
+            self.advance();
+            Some(self.block_body()?)
+        } else {
+            None
+        };
+
+        if catch_body.is_none() && finally_body.is_none() {
+            return Err(self.error(format!(
+                "Expected 'catch' or 'finally' after try block, but found {:?}",
+                self.current
+            )));
+        }
+
+        Ok(StmtKind::Try { body, catch_name, catch_body, finally_body })
+    }
+
+    fn switch_statement(&mut self) -> ParseResult<StmtKind> {
+        self.advance();
+        self.expect(&Token::Symbol('('))?;
+        let subject = self.expression()?;
+        self.expect(&Token::Symbol(')'))?;
+        self.expect(&Token::Symbol('{'))?;
+
+        let context = Context { in_switch: true, ..self.context };
+        let cases = self.with_context(context, |this| this.switch_cases())?;
+        self.expect(&Token::Symbol('}'))?;
+
+        Ok(StmtKind::Switch { subject, cases })
+    }
+
+    fn switch_cases(&mut self) -> ParseResult<Vec<SwitchCase>> {
+        let mut cases = vec![];
+        let mut has_default = false;
+        while self.current != Token::Symbol('}') && self.current != Token::EOF {
+            let test = match self.current {
+                Token::Case => {
+                    self.advance();
+                    Some(self.expression()?)
+                }
+                Token::Default => {
+                    if has_default {
+                        return Err(self.error("Multiple 'default' clauses in switch"));
+                    }
+                    has_default = true;
+                    self.advance();
+                    None
+                }
+                _ => return Err(self.error(format!("Expected 'case' or 'default', but found {:?}", self.current))),
+            };
+            self.expect(&Token::Symbol(':'))?;
+
+            let mut body = vec![];
+            while !matches!(self.current, Token::Case | Token::Default | Token::Symbol('}') | Token::EOF) {
+                body.push(self.recovering_statement());
+            }
+            cases.push(SwitchCase { test, body });
         }
+        Ok(cases)
     }
 
-    fn block(&mut self) -> Stmt {
-        self.expect(&Token::Symbol('{'));
+    // Blocos obrigatórios (try/catch/finally) sempre exigem chaves
+    fn block_body(&mut self) -> ParseResult<Vec<Stmt>> {
+        self.expect(&Token::Symbol('{'))?;
         let mut stmts = vec![];
         while self.current != Token::Symbol('}') && self.current != Token::EOF {
-            stmts.push(self.statement());
+            stmts.push(self.recovering_statement());
         }
-        self.expect(&Token::Symbol('}'));
-        Stmt::Block(stmts)
+        self.expect(&Token::Symbol('}'))?;
+        Ok(stmts)
     }
 
-    fn expression_statement(&mut self) -> Stmt {
-        let expr = self.expression();
+    fn expression_statement(&mut self) -> ParseResult<StmtKind> {
+        let expr = self.expression()?;
         if self.current == Token::Symbol(';') {
             self.advance();
         } else if self.current != Token::Symbol('}') {
-            panic!("Expected ';' after expression, but found {:?}", self.current);
+            return Err(self.error(format!("Expected ';' after expression, but found {:?}", self.current)));
         }
-        Stmt::Expression(expr)
-    }    
+        Ok(StmtKind::Expression(expr))
+    }
 
-    fn expression(&mut self) -> Expr {
+    fn expression(&mut self) -> ParseResult<Expr> {
         self.assignment()
     }
-    
-    fn assignment(&mut self) -> Expr {
-        let expr = self.binary_expr();
-    
-        if let Token::Operator(op) = &self.current {
-            if op == "=" {
-                self.advance();
-                if let Expr::Variable(name) = expr {
-                    let value = self.assignment();
-                    return Expr::Assignment(name, Box::new(value));
-                } else {
-                    panic!("Invalid assignment target");
-                }
-            }
-        }
-    
-        expr
-    }    
 
-    fn binary_expr(&mut self) -> Expr {
-        let mut left = self.primary();
-    
+    fn assignment(&mut self) -> ParseResult<Expr> {
+        let expr = self.binary_expr(0)?;
+
+        let op = match &self.current {
+            Token::Operator(op) if matches!(op.as_str(), "=" | "+=" | "-=" | "*=" | "/=" | "%=") => op.clone(),
+            _ => return Ok(expr),
+        };
+        self.advance();
+        let target = Box::new(assignment_target(expr)?);
+        // Associativa à direita: a = b = c vira a = (b = c)
+        let value = Box::new(self.assignment()?);
+        let span = target.span.to(value.span);
+
+        let kind = if op == "=" {
+            ExprKind::Assignment(target, value)
+        } else {
+            ExprKind::CompoundAssignment(target, op[..1].to_string(), value)
+        };
+        Ok(Expr::new(kind, span))
+    }
+
+    // Precedence climbing: só consome operadores com precedência >= min_prec
+    fn binary_expr(&mut self, min_prec: u8) -> ParseResult<Expr> {
+        let mut left = self.unary()?;
+
         while let Token::Operator(op) = &self.current {
-            if op == "=" { break; } // não trata '=' como operador aqui
+            let prec = match binary_precedence(op) {
+                Some(prec) if prec >= min_prec => prec,
+                _ => break,
+            };
             let op = op.clone();
             self.advance();
-            let right = self.primary();
-            left = Expr::BinaryOp(Box::new(left), op, Box::new(right));
+            // Associativa à esquerda: o lado direito só aceita precedência maior
+            let right = self.binary_expr(prec + 1)?;
+            let span = left.span.to(right.span);
+            left = Expr::new(ExprKind::BinaryOp(Box::new(left), op, Box::new(right)), span);
         }
-    
-        left
-    }    
 
-    fn primary(&mut self) -> Expr {
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> ParseResult<Expr> {
+        let start = self.span;
+
+        if let Token::Operator(op) = &self.current
+            && (op == "++" || op == "--")
+        {
+            let op = op.clone();
+            self.advance();
+            let target = assignment_target(self.unary()?)?;
+            let span = start.to(target.span);
+            return Ok(Expr::new(ExprKind::Update { target: Box::new(target), op, prefix: true }, span));
+        }
+
+        if let Token::Operator(op) = &self.current
+            && (op == "-" || op == "!")
+        {
+            let op = op.clone();
+            self.advance();
+            let operand = self.unary()?;
+            let span = start.to(operand.span);
+            return Ok(Expr::new(ExprKind::Unary(op, Box::new(operand)), span));
+        }
+
+        self.primary()
+    }
+
+    fn primary(&mut self) -> ParseResult<Expr> {
+        let start = self.span;
         let mut expr = match &self.current {
             Token::Number(n) => {
                 let val = *n;
                 self.advance();
-                Expr::Literal(Literal::Number(val))
+                Expr::new(ExprKind::Literal(Literal::Number(val)), start)
             }
             Token::String(s) => {
                 let val = s.clone();
                 self.advance();
-                Expr::Literal(Literal::String(val))
+                Expr::new(ExprKind::Literal(Literal::String(val)), start)
             }
             Token::Boolean(b) => {
                 let val = *b;
                 self.advance();
-                Expr::Literal(Literal::Boolean(val))
+                Expr::new(ExprKind::Literal(Literal::Boolean(val)), start)
+            }
+            Token::Null => {
+                self.advance();
+                Expr::new(ExprKind::Literal(Literal::Null), start)
+            }
+            Token::Template(parts) => {
+                let parts = parts.clone();
+                self.advance();
+                let parts = parts.into_iter()
+                    .map(|part| self.template_part(part, start))
+                    .collect();
+                Expr::new(ExprKind::Template(parts), start)
             }
             Token::Symbol('[') => {
                 self.advance();
                 let mut elements = vec![];
                 while self.current != Token::Symbol(']') {
-                    elements.push(self.expression());
-                    if self.current == Token::Symbol(',') {
-                        self.advance();
-                    }
+                    elements.push(self.expression()?);
+                    self.list_separator(']', "array literal")?;
+                }
+                self.expect(&Token::Symbol(']'))?;
+                Expr::new(ExprKind::Literal(Literal::Array(elements)), self.span_from(start))
+            }
+            Token::Symbol('{') => {
+                let kind = self.object_literal()?;
+                Expr::new(kind, self.span_from(start))
+            }
+            Token::Symbol('(') => match self.arrow_params() {
+                Some(params) => return self.arrow_body(params, start),
+                None => {
+                    self.advance();
+                    let inner = self.expression()?;
+                    self.expect(&Token::Symbol(')'))?;
+                    // O trecho inclui os parênteses
+                    Expr::new(inner.kind, self.span_from(start))
                 }
-                self.expect(&Token::Symbol(']'));
-                Expr::Literal(Literal::Array(elements))
+            },
+            Token::Fn => {
+                let kind = self.function_expr()?;
+                Expr::new(kind, self.span_from(start))
+            }
+            Token::Match => {
+                let (subject, arms) = self.match_arms(true)?;
+                let kind = ExprKind::Match { subject: Box::new(subject), arms };
+                Expr::new(kind, self.span_from(start))
             }
-            Token::Identifier(_) => self.parse_call_or_variable(),
-            Token::Symbol(';') => {
+            Token::Identifier(_) => self.parse_call_or_variable()?,
+            // O literal malformado já foi inteiramente consumido pelo lexer
+            Token::Error(message) | Token::Unterminated(message) => {
+                self.errors.push(AdgError::lex(message.clone(), start));
                 self.advance();
-                Expr::Literal(Literal::Null)
+                Expr::new(ExprKind::Error, start)
             }
-            unexpected => panic!("Unexpected expression: {:?}", unexpected),
+            unexpected => return Err(self.error(format!("Unexpected expression: {:?}", unexpected))),
         };
     
-        // Permitir indexação após qualquer expressão
-        while self.current == Token::Symbol('[') {
+        // Permitir indexação e chamada após qualquer expressão: f(x)[0](y)
+        loop {
+            let kind = match self.current {
+                Token::Symbol('[') => {
+                    self.advance();
+                    let index_expr = self.expression()?;
+                    self.expect(&Token::Symbol(']'))?;
+                    ExprKind::Index(Box::new(expr), Box::new(index_expr))
+                }
+                Token::Symbol('(') => {
+                    let args = self.arguments()?;
+                    ExprKind::Call(Box::new(expr), args)
+                }
+                Token::Symbol('.') => {
+                    self.advance();
+                    let name = match self.property_name() {
+                        Some(name) => name,
+                        None => return Err(self.error(format!("Expected property name after '.', but found {:?}", self.current))),
+                    };
+                    self.advance();
+                    ExprKind::Member(Box::new(expr), name)
+                }
+                Token::Operator(ref op) if op == "++" || op == "--" => {
+                    let op = op.clone();
+                    self.advance();
+                    let target = assignment_target(expr)?;
+                    let kind = ExprKind::Update { target: Box::new(target), op, prefix: false };
+                    return Ok(Expr::new(kind, self.span_from(start)));
+                }
+                _ => break,
+            };
+            expr = Expr::new(kind, self.span_from(start));
+        }
+
+        Ok(expr)
+    }
+
+    // Nome de propriedade: um identificador ou uma palavra reservada (`obj.default`)
+    fn property_name(&self) -> Option<String> {
+        match &self.current {
+            Token::Identifier(name) => Some(name.clone()),
+            token => token.keyword_name().map(str::to_string),
+        }
+    }
+
+    // `{ chave: valor, "outra chave": valor, atalho }`
+    fn object_literal(&mut self) -> ParseResult<ExprKind> {
+        self.expect(&Token::Symbol('{'))?;
+        let mut entries = vec![];
+        while self.current != Token::Symbol('}') {
+            let key_span = self.span;
+            // Só um identificador vale como atalho: `{ match }` não é uma variável
+            let (key, shorthand) = match (&self.current, self.property_name()) {
+                (Token::String(s), _) => (s.clone(), false),
+                (token, Some(name)) => (name, matches!(token, Token::Identifier(_))),
+                (other, None) => return Err(self.error(format!("Expected property name in object literal, but found {:?}", other))),
+            };
             self.advance();
-            let index_expr = self.expression();
-            self.expect(&Token::Symbol(']'));
-            expr = Expr::Index(Box::new(expr), Box::new(index_expr));
+
+            let value = if shorthand && matches!(self.current, Token::Symbol(',') | Token::Symbol('}')) {
+                Expr::new(ExprKind::Variable(key.clone()), key_span)
+            } else {
+                self.expect(&Token::Symbol(':'))?;
+                self.expression()?
+            };
+            entries.push((key, value));
+            self.list_separator('}', "object literal")?;
         }
-    
-        expr
-    }           
+        self.expect(&Token::Symbol('}'))?;
+        Ok(ExprKind::Literal(Literal::Object(entries)))
+    }
+
+    fn arguments(&mut self) -> ParseResult<Vec<Expr>> {
+        self.expect(&Token::Symbol('('))?;
+        let mut args = vec![];
+        while self.current != Token::Symbol(')') {
+            args.push(self.expression()?);
+            self.list_separator(')', "argument list")?;
+        }
+        self.expect(&Token::Symbol(')'))?;
+        Ok(args)
+    }
 
-    fn parse_call_or_variable(&mut self) -> Expr {
-        let mut name = match &self.current {
+    // Depois de um elemento: `,` (opcional após o último) ou o fechamento da lista
+    fn list_separator(&mut self, close: char, list: &str) -> ParseResult<()> {
+        if self.current == Token::Symbol(',') {
+            self.advance();
+        } else if self.current != Token::Symbol(close) {
+            return Err(self.error(format!("Expected ',' or '{}' in {}, but found {:?}", close, list, self.current)));
+        }
+        Ok(())
+    }
+
+    fn parse_call_or_variable(&mut self) -> ParseResult<Expr> {
+        let start = self.span;
+        let name = match &self.current {
             Token::Identifier(s) => s.clone(),
-            _ => panic!("Expected identifier"),
+            _ => return Err(self.error("Expected identifier")),
         };
         self.advance();
 
-        while self.current == Token::Symbol('.') {
+        // `x => x * 2`
+        if self.current == Token::Operator("=>".to_string()) {
             self.advance();
-            let part = match &self.current {
-                Token::Identifier(s) => s.clone(),
-                _ => panic!("Expected identifier after '.'"),
-            };
-            self.advance();
-            name = format!("{}.{}", name, part);
+            return self.arrow_body(vec![name], start);
         }
 
+        Ok(Expr::new(ExprKind::Variable(name), start))
+    }
 
-        let mut expr = Expr::Variable(name.clone());
-        while self.current == Token::Symbol('[') {
-            self.advance();
-            let index_expr = self.expression();
-            self.expect(&Token::Symbol(']'));
-            expr = Expr::Index(Box::new(expr), Box::new(index_expr));
+    // O código de cada `${...}` é analisado como uma expressão independente, mas com
+    // os trechos apontando para o arquivo original; um erro ali não afeta o resto
+    fn template_part(&mut self, part: TemplatePart, template: Span) -> Expr {
+        let (code, span) = match part {
+            TemplatePart::Text(text) => return Expr::new(ExprKind::Literal(Literal::String(text)), template),
+            TemplatePart::Code(code, span) => (code, span),
+        };
+
+        let mut parser = Parser::new(Lexer::with_origin(&code, span));
+        let result = parser.expression().and_then(|expr| match parser.current {
+            Token::EOF => Ok(expr),
+            _ => Err(parser.error(format!("Unexpected {:?} in template interpolation", parser.current))),
+        });
+        self.errors.append(&mut parser.errors);
+
+        result.unwrap_or_else(|err| {
+            self.errors.push(err);
+            Expr::new(ExprKind::Error, span)
+        })
+    }
+}
+
+// Só variáveis, propriedades e índices podem receber atribuição
+fn assignment_target(expr: Expr) -> ParseResult<Expr> {
+    match expr.kind {
+        ExprKind::Variable(_) | ExprKind::Member(..) | ExprKind::Index(..) => Ok(expr),
+        _ => Err(AdgError::parse("Invalid assignment target", expr.span)),
+    }
+}
+
+// Níveis de precedência dos operadores binários (maior = liga mais forte)
+fn binary_precedence(op: &str) -> Option<u8> {
+    match op {
+        "||" => Some(1),
+        "&&" => Some(2),
+        "==" | "!=" => Some(3),
+        "<" | ">" | "<=" | ">=" => Some(4),
+        "+" | "-" => Some(5),
+        "*" | "/" | "%" => Some(6),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_expr(source: &str) -> Expr {
+        let mut parser = Parser::new(Lexer::new(source));
+        parser.expression().unwrap_or_else(|err| panic!("{}: {}", source, err))
+    }
+
+    // Mensagem e trecho do erro de sintaxe produzido por `source`
+    fn parse_error(source: &str) -> (String, Span) {
+        match Parser::new(Lexer::new(source)).parse() {
+            Ok(stmts) => panic!("expected a syntax error for {}, got {:?}", source, stmts),
+            Err(errors) => (errors[0].message(), errors[0].span.expect("syntax errors always have a span")),
         }
+    }
 
-        if self.current == Token::Symbol('(') {
-            self.advance();
-            let mut args = vec![];
-            while self.current != Token::Symbol(')') {
-                args.push(self.expression());
-                if self.current == Token::Symbol(',') {
-                    self.advance();
-                }
+    // Representação compacta da árvore, no estilo S-expression
+    fn sexpr(expr: &Expr) -> String {
+        match &expr.kind {
+            ExprKind::Literal(Literal::Number(n)) => n.to_string(),
+            ExprKind::Literal(Literal::String(s)) => format!("{:?}", s),
+            ExprKind::Literal(Literal::Boolean(b)) => b.to_string(),
+            ExprKind::Literal(Literal::Null) => "null".to_string(),
+            ExprKind::Literal(Literal::Array(items)) => {
+                let items: Vec<String> = items.iter().map(sexpr).collect();
+                format!("[{}]", items.join(" "))
             }
-            self.expect(&Token::Symbol(')'));
-            Expr::Call(name, args)
-        } else {
-            expr // 🧠 Aqui mantemos o expr com possível Index!
-        }        
+            ExprKind::Literal(Literal::Object(entries)) => {
+                let entries: Vec<String> = entries.iter()
+                    .map(|(k, v)| format!("{}: {}", k, sexpr(v)))
+                    .collect();
+                format!("{{{}}}", entries.join(", "))
+            }
+            ExprKind::Variable(name) => name.clone(),
+            ExprKind::Template(parts) => {
+                let parts: Vec<String> = parts.iter().map(sexpr).collect();
+                format!("(template {})", parts.join(" "))
+            }
+            ExprKind::Member(object, name) => format!("(. {} {})", sexpr(object), name),
+            ExprKind::Index(target, index) => format!("(index {} {})", sexpr(target), sexpr(index)),
+            ExprKind::Unary(op, operand) => format!("({} {})", op, sexpr(operand)),
+            ExprKind::BinaryOp(left, op, right) => format!("({} {} {})", op, sexpr(left), sexpr(right)),
+            ExprKind::Call(callee, args) => {
+                let args: Vec<String> = args.iter().map(sexpr).collect();
+                format!("(call {} {})", sexpr(callee), args.join(" "))
+            }
+            ExprKind::Function { params, body } => format!("(fn ({}) {})", params.join(" "), body.len()),
+            ExprKind::Assignment(target, value) => format!("(= {} {})", sexpr(target), sexpr(value)),
+            ExprKind::CompoundAssignment(target, op, value) => format!("({}= {} {})", op, sexpr(target), sexpr(value)),
+            ExprKind::Update { target, op, prefix: true } => format!("(pre{} {})", op, sexpr(target)),
+            ExprKind::Update { target, op, prefix: false } => format!("(post{} {})", op, sexpr(target)),
+            ExprKind::Match { subject, arms } => format!("(match {} {})", sexpr(subject), arms.len()),
+            ExprKind::Error => "<error>".to_string(),
+        }
+    }
+
+    #[test]
+    fn binary_precedence_and_associativity() {
+        let cases = [
+            ("1 + 2 * 3", "(+ 1 (* 2 3))"),
+            ("1 * 2 + 3", "(+ (* 1 2) 3)"),
+            ("1 - 2 - 3", "(- (- 1 2) 3)"),
+            ("8 / 4 / 2", "(/ (/ 8 4) 2)"),
+            ("1 + 2 - 3", "(- (+ 1 2) 3)"),
+            ("a + 1 > b", "(> (+ a 1) b)"),
+            ("a < b == c > d", "(== (< a b) (> c d))"),
+            ("a == b != c", "(!= (== a b) c)"),
+            ("a >= 1 * 2 + 3", "(>= a (+ (* 1 2) 3))"),
+            ("(1 + 2) * 3", "(* (+ 1 2) 3)"),
+            ("2 * (3 + (4 - 1))", "(* 2 (+ 3 (- 4 1)))"),
+            ("x[1 + 1] * 2", "(* (index x (+ 1 1)) 2)"),
+            ("f(1 + 2, 3) - 1", "(- (call f (+ 1 2) 3) 1)"),
+            ("a = 1 + 2", "(= a (+ 1 2))"),
+            ("a = b = c", "(= a (= b c))"),
+            ("a = b == c", "(= a (== b c))"),
+            ("a || b && c", "(|| a (&& b c))"),
+            ("a && b || c && d", "(|| (&& a b) (&& c d))"),
+            ("a || b || c", "(|| (|| a b) c)"),
+            ("x > 1 && x < 5", "(&& (> x 1) (< x 5))"),
+            ("a == b || !c", "(|| (== a b) (! c))"),
+            ("n % 2 == 0", "(== (% n 2) 0)"),
+            ("a + b % c * d", "(+ a (* (% b c) d))"),
+            ("ok = a && b", "(= ok (&& a b))"),
+        ];
+
+        for (source, expected) in cases {
+            assert_eq!(sexpr(&parse_expr(source)), expected, "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn unary_operators_bind_tighter_than_binary() {
+        let cases = [
+            ("-x", "(- x)"),
+            ("!done", "(! done)"),
+            ("-(a + b)", "(- (+ a b))"),
+            ("-2 * 3", "(* (- 2) 3)"),
+            ("a - -b", "(- a (- b))"),
+            ("!!flag", "(! (! flag))"),
+            ("!a == b", "(== (! a) b)"),
+            ("-x[0]", "(- (index x 0))"),
+        ];
+
+        for (source, expected) in cases {
+            assert_eq!(sexpr(&parse_expr(source)), expected, "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn switch_collects_cases_in_order() {
+        let mut parser = Parser::new(Lexer::new(r#"
+            switch (op) {
+                case "a":
+                case 1 + 1:
+                    x = 1;
+                    break;
+                default:
+                    x = 2;
+                case "z":
+                    x = 3;
+            }
+        "#));
+
+        let StmtKind::Switch { subject, cases } = &parser.parse().unwrap()[0].kind else {
+            panic!("expected switch statement");
+        };
+        assert_eq!(sexpr(subject), "op");
+
+        let summary: Vec<(String, usize)> = cases.iter()
+            .map(|c| (c.test.as_ref().map(sexpr).unwrap_or("default".into()), c.body.len()))
+            .collect();
+        assert_eq!(summary, vec![
+            ("\"a\"".to_string(), 0),
+            ("(+ 1 1)".to_string(), 2),
+            ("default".to_string(), 1),
+            ("\"z\"".to_string(), 1),
+        ]);
+    }
+
+    #[test]
+    fn switch_rejects_duplicate_default() {
+        let (message, span) = parse_error("switch (x) { default: default: }");
+        assert_eq!(message, "Multiple 'default' clauses in switch");
+        assert_eq!((span.line, span.column), (1, 23));
+    }
+
+    #[test]
+    fn while_and_do_while_loops() {
+        let mut parser = Parser::new(Lexer::new(r#"
+            while (i < 3) i = i + 1;
+            do { i = i - 1; } while (i > 0);
+        "#));
+        let stmts = parser.parse().unwrap();
+
+        let StmtKind::Looping { condition, body } = &stmts[0].kind else {
+            panic!("expected while to parse as a looping statement");
+        };
+        assert_eq!(sexpr(condition), "(< i 3)");
+        assert_eq!(body.len(), 1);
+
+        let StmtKind::DoWhile { body, condition } = &stmts[1].kind else {
+            panic!("expected do-while statement");
+        };
+        assert_eq!(sexpr(condition), "(> i 0)");
+        assert_eq!(body.len(), 1);
+    }
+
+    #[test]
+    fn function_expressions_and_arbitrary_callees() {
+        let cases = [
+            ("fn (a, b) { return a + b; }", "(fn (a b) 1)"),
+            ("fn () { }", "(fn () 0)"),
+            ("(a) => a * 2", "(fn (a) 1)"),
+            ("() => 1", "(fn () 1)"),
+            ("(a, b) => { let c = a; return c + b; }", "(fn (a b) 2)"),
+            ("x => x", "(fn (x) 1)"),
+            ("(a) + 1", "(+ a 1)"),
+            ("handlers[0](x)", "(call (index handlers 0) x)"),
+            ("make()(1)(2)", "(call (call (call make ) 1) 2)"),
+            ("f(x)[1]", "(index (call f x) 1)"),
+            ("math.sqrt(4)", "(call (. math sqrt) 4)"),
+            ("(fn (n) { return n; })(3)", "(call (fn (n) 1) 3)"),
+            ("apply((x) => x + 1, 2)", "(call apply (fn (x) 1) 2)"),
+            // A vírgula depois do último elemento é opcional
+            ("f(1, 2,)", "(call f 1 2)"),
+            ("[1, [2,],]", "[1 [2]]"),
+            ("(a, b,) => a", "(fn (a b) 1)"),
+            ("fn (a,) { return a; }", "(fn (a) 1)"),
+        ];
+
+        for (source, expected) in cases {
+            assert_eq!(sexpr(&parse_expr(source)), expected, "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn object_literals_and_member_access() {
+        let cases = [
+            ("{}", "{}"),
+            (r#"{ nome: "Ana", "a b": 1 + 2, }"#, r#"{nome: "Ana", a b: (+ 1 2)}"#),
+            ("{ x, y: { z } }", "{x: x, y: {z: z}}"),
+            ("a.b.c", "(. (. a b) c)"),
+            (r#"a["b"].c"#, r#"(. (index a "b") c)"#),
+            ("f().x[0]", "(index (. (call f ) x) 0)"),
+            ("a.b.c = 1", "(= (. (. a b) c) 1)"),
+            ("p.x + p.y * 2", "(+ (. p x) (* (. p y) 2))"),
+            // Palavras reservadas valem como nome de propriedade
+            ("opts.default", "(. opts default)"),
+            ("m.match(x).class", "(. (call (. m match) x) class)"),
+            ("{ match: 1, default: 2, null: 3 }", "{match: 1, default: 2, null: 3}"),
+            ("{ import: a.export, finally: b.do }", "{import: (. a export), finally: (. b do)}"),
+        ];
+
+        for (source, expected) in cases {
+            assert_eq!(sexpr(&parse_expr(source)), expected, "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn index_member_and_compound_assignment() {
+        let cases = [
+            ("arr[i] = v", "(= (index arr i) v)"),
+            ("m[0][1] = 2", "(= (index (index m 0) 1) 2)"),
+            ("obj.k = v", "(= (. obj k) v)"),
+            ("a += 1", "(+= a 1)"),
+            ("a.b -= 2 * 3", "(-= (. a b) (* 2 3))"),
+            ("a[0] *= b /= 2", "(*= (index a 0) (/= b 2))"),
+            ("n %= 2", "(%= n 2)"),
+            ("`a${x + 1}b${f(`${y}`)}`", r#"(template "a" (+ x 1) "b" (call f (template y)))"#),
+            ("i++", "(post++ i)"),
+            ("--arr[0]", "(pre-- (index arr 0))"),
+            ("x = i++ + ++j", "(= x (+ (post++ i) (pre++ j)))"),
+            ("a - -b", "(- a (- b))"),
+        ];
+
+        for (source, expected) in cases {
+            assert_eq!(sexpr(&parse_expr(source)), expected, "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn update_requires_assignable_target() {
+        let (message, span) = parse_error("x = (a + b)++;");
+        assert_eq!(message, "Invalid assignment target");
+        assert_eq!((span.start, span.end), (4, 11));
+    }
+
+    #[test]
+    fn doc_comments_attach_to_function_declarations() {
+        let mut parser = Parser::new(Lexer::new("
+            /// Dobra um número.
+            fn dobro(x) { return x * 2; } // fim
+
+            // comentário comum
+            fn triplo(x) { return x * 3; }
+        "));
+        let stmts = parser.parse().unwrap();
+
+        let docs: Vec<Option<&str>> = stmts.iter()
+            .map(|stmt| match &stmt.kind {
+                StmtKind::Function { doc, .. } => doc.as_deref(),
+                _ => panic!("expected function declaration"),
+            })
+            .collect();
+        assert_eq!(docs, vec![Some("Dobra um número."), None]);
+    }
+
+    #[test]
+    fn assignment_requires_variable_target() {
+        let (message, span) = parse_error("1 + 2 = 3;");
+        assert_eq!(message, "Invalid assignment target");
+        assert_eq!((span.start, span.end), (0, 5));
+    }
+
+    #[test]
+    fn nodes_span_their_source_text() {
+        let source = "let total = soma(a, b) * 2;\nprint(`x = ${total + 1}`);";
+        let stmts = Parser::new(Lexer::new(source)).parse().unwrap();
+        let text = |span: Span| &source[span.start..span.end];
+
+        assert_eq!(text(stmts[0].span), "let total = soma(a, b) * 2;");
+        let StmtKind::VarDecl { value, .. } = &stmts[0].kind else {
+            panic!("expected variable declaration");
+        };
+        assert_eq!(text(value.span), "soma(a, b) * 2");
+        let ExprKind::BinaryOp(call, _, _) = &value.kind else {
+            panic!("expected binary operation");
+        };
+        assert_eq!(text(call.span), "soma(a, b)");
+
+        // Expressões dentro de `${...}` apontam para o arquivo original
+        let StmtKind::Expression(print) = &stmts[1].kind else {
+            panic!("expected expression statement");
+        };
+        let ExprKind::Call(_, args) = &print.kind else {
+            panic!("expected call");
+        };
+        let ExprKind::Template(parts) = &args[0].kind else {
+            panic!("expected template");
+        };
+        assert_eq!(text(parts[1].span), "total + 1");
+        assert_eq!((parts[1].span.line, parts[1].span.column), (2, 14));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn syntax_errors_point_at_the_offending_token() {
+        let cases = [
+            ("let x = 1\nlet y = 2;", "Expected Symbol(';'), but found Let", (2, 1)),
+            ("print(1 +);", "Unexpected expression: Symbol(')')", (1, 10)),
+            ("let s = \"abc\\q\";", "Unknown escape sequence '\\q'", (1, 9)),
+            ("let n = 0b12;", "Invalid number literal '0b12': invalid digit '2' in binary literal", (1, 9)),
+            ("`${ 1 + }`;", "Unexpected expression: EOF", (1, 9)),
+            ("fn f() { break; }", "'break' outside of a loop or switch", (1, 10)),
+            ("while (true) { fn g() { continue; } }", "'continue' outside of a loop", (1, 25)),
+            ("switch (x) { case 1: continue; }", "'continue' outside of a loop", (1, 22)),
+            ("let o = { match };", "Expected Symbol(':'), but found Symbol('}')", (1, 17)),
+            ("f(a b);", "Expected ',' or ')' in argument list, but found Identifier(\"b\")", (1, 5)),
+            ("fn g(a b) {}", "Expected ',' or ')' in parameter list, but found Identifier(\"b\")", (1, 8)),
+            ("let l = [1 2];", "Expected ',' or ']' in array literal, but found Number(2.0)", (1, 12)),
+            ("let h = (a b) => a;", "Expected Symbol(')'), but found Identifier(\"b\")", (1, 12)),
+            ("let o = { a: 1 b: 2 };", "Expected ',' or '}' in object literal, but found Identifier(\"b\")", (1, 16)),
+        ];
+
+        for (source, message, position) in cases {
+            let (actual, span) = parse_error(source);
+            assert_eq!(actual, message, "source: {}", source);
+            assert_eq!((span.line, span.column), position, "source: {}", source);
+        }
+    }
+
+    // Resumo de cada statement do topo: nome declarado, "expr" ou "error"
+    fn outline(stmts: &[Stmt]) -> Vec<&str> {
+        stmts.iter()
+            .map(|stmt| match &stmt.kind {
+                StmtKind::VarDecl { name, .. } | StmtKind::Function { name, .. } => name.as_str(),
+                StmtKind::Expression(_) => "expr",
+                StmtKind::Error => "error",
+                _ => "other",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn recovers_and_reports_every_syntax_error() {
+        let source = [
+            "let a = 1",
+            "let b = ;",
+            "fn f(x) {",
+            "    let t = x + * 2;",
+            "    return t;",
+            "}",
+            r#"let s = "\q" + 0b12;"#,
+            "print(`${ a + }`);",
+            "let ok = f(1);",
+            "fn g() {",
+            r#"    let u = "sem fim;"#,
+            "    print(u);",
+            "}",
+        ].join("\n");
+        let (stmts, errors) = Parser::new(Lexer::new(&source)).parse_partial();
+
+        let found: Vec<(usize, usize, String)> = errors.iter()
+            .map(|err| {
+                let span = err.span.unwrap();
+                (span.line, span.column, err.message())
+            })
+            .collect();
+        assert_eq!(found, vec![
+            (2, 1, "Expected Symbol(';'), but found Let".to_string()),
+            (2, 9, "Unexpected expression: Symbol(';')".to_string()),
+            (4, 17, "Unexpected expression: Operator(\"*\")".to_string()),
+            (7, 9, "Unknown escape sequence '\\q'".to_string()),
+            (7, 16, "Invalid number literal '0b12': invalid digit '2' in binary literal".to_string()),
+            (8, 15, "Unexpected expression: EOF".to_string()),
+            (11, 13, "Unterminated string literal".to_string()),
+        ]);
+
+        // A árvore parcial mantém tudo o que pôde ser analisado
+        assert_eq!(outline(&stmts), vec!["error", "error", "f", "s", "expr", "ok", "error"]);
+        let StmtKind::Function { body, .. } = &stmts[2].kind else {
+            panic!("expected function declaration");
+        };
+        assert!(matches!(body[0].kind, StmtKind::Error));
+        assert!(matches!(body[1].kind, StmtKind::Return(Some(_))));
+        let StmtKind::VarDecl { value, .. } = &stmts[3].kind else {
+            panic!("expected variable declaration");
+        };
+        assert_eq!(sexpr(value), "(+ <error> <error>)");
+    }
+
+    #[test]
+    fn synchronizes_past_blocks_and_at_closing_braces() {
+        let cases = [
+            ("if (x y) { a = 1; } let z = 2;", vec!["error", "z"], 1),
+            ("fn g() { let = 1; let w = 2; } let z = 3;", vec!["g", "z"], 1),
+            ("while (true) { ) } let z = 4;", vec!["other", "z"], 1),
+            ("let a = [1, 2; let z = 5;", vec!["error", "z"], 1),
+            ("}} let z = 6; ) let y = 7;", vec!["error", "error", "z", "error", "y"], 3),
+        ];
+
+        for (source, expected, error_count) in cases {
+            let (stmts, errors) = Parser::new(Lexer::new(source)).parse_partial();
+            assert_eq!(outline(&stmts), expected, "source: {}", source);
+            assert_eq!(errors.len(), error_count, "source: {}: {:?}", source, errors);
+        }
+    }
+
+    #[test]
+    fn import_and_export_declarations() {
+        let stmts = Parser::new(Lexer::new(r#"
+            import util from "./util.adg";
+            import { soma, media as m } from "./math.adg";
+            export const PI = 3.14;
+            /// Dobra
+            export fn dobro(x) { return x * 2; }
+        "#)).parse().unwrap_or_else(|errors| panic!("{}", errors[0]));
+
+        let StmtKind::Import { clause: ImportClause::Module(name), source } = &stmts[0].kind else {
+            panic!("expected module import");
+        };
+        assert_eq!((name.as_str(), source.as_str()), ("util", "./util.adg"));
+
+        let StmtKind::Import { clause: ImportClause::Names(names), .. } = &stmts[1].kind else {
+            panic!("expected named import");
+        };
+        assert_eq!(names, &[("soma".to_string(), "soma".to_string()), ("media".to_string(), "m".to_string())]);
+
+        let exported: Vec<&str> = stmts[2..].iter()
+            .map(|stmt| match &stmt.kind {
+                StmtKind::Export(decl) => outline(std::slice::from_ref(decl))[0],
+                _ => panic!("expected export"),
+            })
+            .collect();
+        assert_eq!(exported, vec!["PI", "dobro"]);
+        let StmtKind::Export(decl) = &stmts[3].kind else { unreachable!() };
+        assert!(matches!(&decl.kind, StmtKind::Function { doc: Some(doc), .. } if doc == "Dobra"));
+    }
+
+    #[test]
+    fn import_and_export_only_at_top_level() {
+        let cases = [
+            ("fn f() { import a from \"./a.adg\"; }", "'import' is only allowed at the top level of a file"),
+            ("if (x) { export let a = 1; }", "'export' is only allowed at the top level of a file"),
+            ("export print(1);", "Expected 'let', 'const', 'fn', 'struct' or 'enum' after 'export', but found Identifier(\"print\")"),
+            ("import a \"./a.adg\";", "Expected 'from', but found String(\"./a.adg\")"),
+            ("import { a } from util;", "Expected module path string, but found Identifier(\"util\")"),
+        ];
+
+        for (source, expected) in cases {
+            assert_eq!(parse_error(source).0, expected, "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn struct_declarations() {
+        let stmts = Parser::new(Lexer::new(r#"
+            struct Point {
+                x,
+                y = 1 + 1;
+                fn norm() { return this.x; }
+                fn init(x) { this.x = x; }
+            }
+            export class Empty {}
+        "#)).parse().unwrap_or_else(|errors| panic!("{}", errors[0]));
+
+        let StmtKind::Struct { name, fields, methods } = &stmts[0].kind else {
+            panic!("expected struct declaration");
+        };
+        assert_eq!(name, "Point");
+        let fields: Vec<(&str, Option<String>)> = fields.iter()
+            .map(|field| (field.name.as_str(), field.default.as_ref().map(sexpr)))
+            .collect();
+        assert_eq!(fields, vec![("x", None), ("y", Some("(+ 1 1)".to_string()))]);
+        let methods: Vec<(&str, usize, usize)> = methods.iter()
+            .map(|method| (method.name.as_str(), method.params.len(), method.body.len()))
+            .collect();
+        assert_eq!(methods, vec![("norm", 0, 1), ("init", 1, 1)]);
+
+        let StmtKind::Export(decl) = &stmts[1].kind else { panic!("expected export") };
+        assert!(matches!(&decl.kind, StmtKind::Struct { name, fields, methods }
+            if name == "Empty" && fields.is_empty() && methods.is_empty()));
+    }
+
+    #[test]
+    fn struct_declaration_errors() {
+        let cases = [
+            ("struct { x }", "Expected struct name", (1, 8)),
+            ("struct P { x, fn x() {} }", "Duplicate member 'x' in struct P", (1, 15)),
+            ("struct P { 1 }", "Expected field or method in struct", (1, 12)),
+            ("class P { fn () {} }", "Expected method name", (1, 14)),
+        ];
+
+        for (source, message, position) in cases {
+            let (actual, span) = parse_error(source);
+            assert_eq!(actual, message, "source: {}", source);
+            assert_eq!((span.line, span.column), position, "source: {}", source);
+        }
+    }
+
+    #[test]
+    fn enum_declarations_and_match_patterns() {
+        let stmts = Parser::new(Lexer::new(r#"
+            export enum Shape { Circle(r), Rect(w, h), Empty, }
+            let area = match (s) {
+                Shape.Circle(r) => 3 * r * r,
+                Shape.Rect(w, _) if (w > 0) => { w }
+                [first, ...rest] => first,
+                { x, y: -1 } => x,
+                "a" => 1,
+                Shape.Empty => 0,
+                _ => 0
+            };
+        "#)).parse().unwrap_or_else(|errors| panic!("{}", errors[0]));
+
+        let StmtKind::Export(decl) = &stmts[0].kind else { panic!("expected export") };
+        let StmtKind::Enum { name, variants } = &decl.kind else { panic!("expected enum declaration") };
+        let variants: Vec<(&str, usize)> = variants.iter().map(|v| (v.name.as_str(), v.fields.len())).collect();
+        assert_eq!((name.as_str(), variants), ("Shape", vec![("Circle", 1), ("Rect", 2), ("Empty", 0)]));
+
+        let StmtKind::VarDecl { value, .. } = &stmts[1].kind else { panic!("expected variable declaration") };
+        let ExprKind::Match { arms, .. } = &value.kind else { panic!("expected match expression") };
+        let bindings: Vec<Vec<&str>> = arms.iter().map(|arm| arm.pattern.bindings()).collect();
+        assert_eq!(bindings, vec![
+            vec!["r"], vec!["w"], vec!["first", "rest"], vec!["x"], vec![], vec![], vec![],
+        ]);
+        assert!(arms[1].guard.is_some() && arms[0].guard.is_none());
+        assert!(matches!(&arms[3].pattern, Pattern::Object(entries)
+            if matches!(entries[1].1, Pattern::Literal(Literal::Number(n)) if n == -1.0)));
+        assert!(matches!(&arms[5].pattern, Pattern::Variant { args: None, .. }));
+        assert!(arms[6].pattern.is_irrefutable());
+    }
+
+    #[test]
+    fn match_errors() {
+        let cases = [
+            ("enum E { A, A(x) }", "Duplicate variant 'A' in enum E", (1, 13)),
+            ("match (x) { [a, a] => 1 }", "Duplicate binding 'a' in pattern", (1, 13)),
+            ("match (x) { 1 + 2 => 1 }", "Expected operator '=>', but found Operator(\"+\")", (1, 15)),
+            ("match (x) { a if a => 1 }", "Expected Symbol('('), but found Identifier(\"a\")", (1, 18)),
+            ("fn f() { let y = match (x) { _ => { return 1; } }; }", "'return' inside a match expression", (1, 37)),
+            ("looping (true) { let y = match (x) { _ => { break; } }; }", "'break' outside of a loop or switch", (1, 45)),
+        ];
+
+        for (source, message, position) in cases {
+            let (actual, span) = parse_error(source);
+            assert_eq!(actual, message, "source: {}", source);
+            assert_eq!((span.line, span.column), position, "source: {}", source);
+        }
+    }
+}
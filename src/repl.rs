@@ -0,0 +1,348 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::{Editor, Helper};
+use crate::error::AdgError;
+use crate::interpreter::{Interpreter, Value};
+use crate::lexer::{Lexer, Token};
+use crate::parser::{Parser, Stmt};
+use crate::span::Span;
+
+const HELP: &str = "\
+Comandos:
+  :load arquivo.adg  executa um arquivo nesta sessão
+  :env               lista as variáveis declaradas
+  :reset             descarta todas as variáveis
+  :help              mostra esta ajuda
+  :quit              sai (também com Ctrl-D)";
+
+/// Sessão do REPL: um único interpretador vivo entre as entradas. Cada entrada é
+/// registrada nas fontes do interpretador, então um erro numa função definida
+/// antes ainda aponta para o código certo.
+pub struct Repl {
+    interpreter: Interpreter,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Repl { interpreter: Interpreter::new() }
+    }
+
+    /// Avalia uma entrada (código ou comando `:`) e devolve o texto a mostrar,
+    /// ou a mensagem de erro.
+    pub fn handle(&mut self, input: &str) -> Result<Option<String>, String> {
+        let input = input.trim();
+        if let Some(command) = input.strip_prefix(':') {
+            return self.command(command);
+        }
+        self.eval("<repl>", input)
+    }
+
+    /// Avalia código na sessão e devolve o valor da última expressão, se houver;
+    /// `path` é o nome mostrado nas mensagens de erro.
+    pub fn eval(&mut self, path: &str, input: &str) -> Result<Option<String>, String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(None);
+        }
+
+        let origin = self.interpreter.add_source(path, input);
+        let stmts = match parse(input, origin) {
+            Ok(stmts) => stmts,
+            // O `;` final é opcional no REPL: `1 + 2` vale como `1 + 2;`
+            Err(errors) => parse(&with_semicolon(input), origin).map_err(|_| self.report(&errors))?,
+        };
+
+        // `null` não é mostrado, para que `print(...)` não polua a saída
+        match self.interpreter.evaluate(stmts).map_err(|err| self.report(&[err]))? {
+            Some(Value::Null) | None => Ok(None),
+            Some(value) => Ok(Some(value.as_string())),
+        }
+    }
+
+    fn command(&mut self, command: &str) -> Result<Option<String>, String> {
+        let (name, argument) = match command.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (command, ""),
+        };
+
+        match name {
+            "load" => self.load(argument),
+            "env" => Ok(Some(self.env_listing())),
+            "reset" => {
+                self.interpreter = Interpreter::new();
+                Ok(Some("Sessão reiniciada.".to_string()))
+            }
+            "help" => Ok(Some(HELP.to_string())),
+            _ => Err(format!("erro: comando desconhecido ':{}' (use :help)", name)),
+        }
+    }
+
+    fn load(&mut self, path: &str) -> Result<Option<String>, String> {
+        if path.is_empty() {
+            return Err("erro: uso: :load arquivo.adg".to_string());
+        }
+        let code = fs::read_to_string(path)
+            .map_err(|err| format!("erro: não foi possível ler o arquivo {}: {}", path, err))?;
+
+        self.interpreter.run_file(path, &code).map_err(|errors| self.report(&errors))?;
+        Ok(Some(format!("{} carregado.", path)))
+    }
+
+    fn env_listing(&self) -> String {
+        let bindings = self.interpreter.bindings();
+        if bindings.is_empty() {
+            return "(nenhuma variável declarada)".to_string();
+        }
+        bindings.iter()
+            .map(|(name, value)| format!("{} = {}", name, value.as_string()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn report(&self, errors: &[AdgError]) -> String {
+        errors.iter()
+            .map(|err| err.report_in(self.interpreter.sources()))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+fn parse(code: &str, origin: Span) -> Result<Vec<Stmt>, Vec<AdgError>> {
+    Parser::new(Lexer::with_origin(code, origin)).parse()
+}
+
+// Insere `;` logo após o último token (antes de um eventual comentário no fim)
+fn with_semicolon(input: &str) -> String {
+    let mut lexer = Lexer::new(input);
+    let mut end = input.len();
+    while lexer.next_token() != Token::EOF {
+        end = lexer.span().end;
+    }
+    let mut code = input.to_string();
+    code.insert(end, ';');
+    code
+}
+
+/// A entrada continua na próxima linha se houver `{`, `(` ou `[` sem fechar, ou
+/// uma template string ou comentário de bloco ainda aberto.
+pub fn is_incomplete(input: &str) -> bool {
+    let mut lexer = Lexer::new(input);
+    let mut depth = 0;
+    loop {
+        match lexer.next_token() {
+            Token::EOF => return depth > 0,
+            Token::Symbol('{' | '(' | '[') => depth += 1,
+            Token::Symbol('}' | ')' | ']') => depth -= 1,
+            // Uma string comum sem fim é um erro, não uma linha a continuar
+            Token::Unterminated(_) => {
+                let rest = &input[lexer.span().start..];
+                return rest.starts_with('`') || rest.starts_with("/*");
+            }
+            _ => {}
+        }
+    }
+}
+
+// Integração com o rustyline: só a validação (entrada multilinha) é customizada
+struct ReplHelper;
+
+impl Helper for ReplHelper {}
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {
+    fn validate(&self, ctx: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
+        let input = ctx.input();
+        if !input.trim_start().starts_with(':') && is_incomplete(input) {
+            Ok(ValidationResult::Incomplete)
+        } else {
+            Ok(ValidationResult::Valid(None))
+        }
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".adg_history"))
+}
+
+/// Inicia o REPL interativo; termina com `:quit` ou Ctrl-D.
+pub fn run() -> ExitCode {
+    let mut editor: Editor<ReplHelper, DefaultHistory> = match Editor::new() {
+        Ok(editor) => editor,
+        Err(err) => {
+            eprintln!("erro: não foi possível iniciar o REPL: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    editor.set_helper(Some(ReplHelper));
+
+    let history = history_path();
+    if let Some(path) = &history {
+        // Na primeira execução o arquivo ainda não existe
+        let _ = editor.load_history(path);
+    }
+
+    println!("ADG {} — digite :help para ver os comandos", env!("CARGO_PKG_VERSION"));
+    let mut repl = Repl::new();
+    loop {
+        match editor.readline("adg> ") {
+            Ok(line) => {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let _ = editor.add_history_entry(line.as_str());
+                if matches!(line.trim(), ":quit" | ":exit") {
+                    break;
+                }
+                match repl.handle(&line) {
+                    Ok(Some(output)) => println!("{}", output),
+                    Ok(None) => {}
+                    Err(message) => eprintln!("{}", message),
+                }
+            }
+            // Ctrl-C descarta só a entrada atual
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => {
+                eprintln!("erro: {}", err);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if let Some(path) = &history {
+        let _ = editor.save_history(path);
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(repl: &mut Repl, input: &str) -> Option<String> {
+        repl.handle(input).unwrap_or_else(|err| panic!("{}: {}", input, err))
+    }
+
+    #[test]
+    fn keeps_state_and_prints_expression_results() {
+        let mut repl = Repl::new();
+
+        assert_eq!(output(&mut repl, "let x = 20;"), None);
+        assert_eq!(output(&mut repl, "x + 1"), Some("21".into()));
+        assert_eq!(output(&mut repl, "fn dobro(n) {\n  return n * 2;\n}"), None);
+        assert_eq!(output(&mut repl, "dobro(x) // comentário"), Some("40".into()));
+        assert_eq!(output(&mut repl, "x = x + 2; `x vale ${x}`"), Some("x vale 22".into()));
+        assert_eq!(output(&mut repl, "let itens = [1, 2]"), None);
+        assert_eq!(output(&mut repl, "itens"), Some("[1, 2]".into()));
+        assert_eq!(output(&mut repl, "if (x > 1) { x = 0; }"), None);
+        assert_eq!(output(&mut repl, "x"), Some("0".into()));
+    }
+
+    #[test]
+    fn top_level_declarations_can_be_retyped() {
+        let mut repl = Repl::new();
+
+        output(&mut repl, "let x = 1;");
+        output(&mut repl, "fn f() { return x; }");
+        assert_eq!(output(&mut repl, "let x = 2;"), None);
+        assert_eq!(output(&mut repl, "f()"), Some("2".into()));
+        assert_eq!(output(&mut repl, "fn f() { return x * 10; }"), None);
+        assert_eq!(output(&mut repl, "f()"), Some("20".into()));
+        assert_eq!(output(&mut repl, "const x = 3; x"), Some("3".into()));
+        assert!(repl.handle("x = 4").unwrap_err().contains("Assignment to constant variable 'x'"));
+        assert_eq!(output(&mut repl, "let x = 5; x"), Some("5".into()));
+
+        // Fora do topo a regra continua a mesma
+        let err = repl.handle("{ let y = 1; let y = 2; }").unwrap_err();
+        assert!(err.contains("Variable 'y' already declared in this scope"), "{}", err);
+        let err = repl.handle("fn g() { let y = 1; let y = 2; } g()").unwrap_err();
+        assert!(err.contains("Variable 'y' already declared in this scope"), "{}", err);
+    }
+
+    #[test]
+    fn errors_point_at_the_input_that_defined_the_code() {
+        let mut repl = Repl::new();
+        output(&mut repl, "fn f(o) {\n  return o.x.y;\n}");
+        output(&mut repl, "let a = 1;");
+
+        let err = repl.handle("f({ x: a })").unwrap_err();
+        assert!(err.contains("TypeError: Cannot read property 'y' of number"), "{}", err);
+        assert!(err.contains("--> <repl>:2:10"), "{}", err);
+        assert!(err.contains("2 |   return o.x.y;"), "{}", err);
+
+        // Um erro não derruba a sessão
+        assert_eq!(output(&mut repl, "a"), Some("1".into()));
+
+        let err = repl.handle("let = 1;").unwrap_err();
+        assert!(err.contains("SyntaxError: Expected identifier after let/const"), "{}", err);
+    }
+
+    #[test]
+    fn meta_commands() {
+        let path = env::temp_dir().join(format!("adg-repl-{}.adg", std::process::id()));
+        fs::write(&path, "let carregado = 1;\nconst nome = \"adg\";\nfn falha() { return 1 - \"a\"; }\n").unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        let mut repl = Repl::new();
+        assert_eq!(output(&mut repl, ":env"), Some("(nenhuma variável declarada)".into()));
+        assert_eq!(output(&mut repl, &format!(":load {}", path)), Some(format!("{} carregado.", path)));
+        output(&mut repl, "let x = [carregado, 2];");
+        assert_eq!(
+            output(&mut repl, ":env"),
+            Some("carregado = 1\nfalha = [Function]\nnome = adg\nx = [1, 2]".into()),
+        );
+
+        let err = repl.handle("falha()").unwrap_err();
+        assert!(err.contains(&format!("--> {}:3:21", path)), "{}", err);
+
+        assert_eq!(output(&mut repl, ":reset"), Some("Sessão reiniciada.".into()));
+        assert_eq!(output(&mut repl, ":env"), Some("(nenhuma variável declarada)".into()));
+        assert!(repl.handle(":foo").unwrap_err().contains("comando desconhecido ':foo'"));
+        assert!(repl.handle(":load /nao/existe.adg").is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn detects_incomplete_input() {
+        let incomplete = [
+            "fn f() {",
+            "if (x) {\n  print(1);",
+            "print(1,",
+            "let a = [1,\n2",
+            "`linha ${x}",
+            "/* ainda",
+        ];
+        let complete = [
+            "1 + 2",
+            "fn f() { return 1; }",
+            "let s = \"{\";",
+            "}",
+            "let s = \"sem fim",
+        ];
+
+        for input in incomplete {
+            assert!(is_incomplete(input), "{:?} should be incomplete", input);
+        }
+        for input in complete {
+            assert!(!is_incomplete(input), "{:?} should be complete", input);
+        }
+    }
+}
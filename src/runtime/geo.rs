@@ -0,0 +1,726 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use indexmap::IndexMap;
+use crate::error::AdgError;
+use crate::interpreter::Value;
+
+// Raio médio da Terra (IUGG), usado nos cálculos esféricos
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+// Elipsoide WGS-84, usado pelo Vincenty
+const WGS84_A: f64 = 6378137.0;
+const WGS84_F: f64 = 1.0 / 298.257223563;
+
+pub fn get_geo_module() -> HashMap<String, Value> {
+    let mut map = HashMap::new();
+
+    map.insert("point".to_string(), Value::NativeFunction(point));
+    map.insert("haversine".to_string(), Value::NativeFunction(haversine));
+    map.insert("vincenty".to_string(), Value::NativeFunction(vincenty));
+    map.insert("bearing".to_string(), Value::NativeFunction(bearing));
+    map.insert("destination".to_string(), Value::NativeFunction(destination));
+    map.insert("bbox".to_string(), Value::NativeFunction(bbox));
+    map.insert("pointInPolygon".to_string(), Value::NativeFunction(point_in_polygon));
+    map.insert("toDMS".to_string(), Value::NativeFunction(to_dms));
+    map.insert("fromDMS".to_string(), Value::NativeFunction(from_dms));
+    map.insert("parseGeoJSON".to_string(), Value::NativeFunction(parse_geojson));
+    map.insert("stringifyGeoJSON".to_string(), Value::NativeFunction(stringify_geojson));
+
+    map
+}
+
+fn expect_args(args: &[Value], count: usize, function: &str) -> Result<(), AdgError> {
+    if args.len() != count {
+        let plural = if count == 1 { "argumento" } else { "argumentos" };
+        return Err(AdgError::arity_error(format!("geo.{} espera {} {}", function, count, plural)));
+    }
+    Ok(())
+}
+
+fn point_value(lat: f64, lon: f64) -> Value {
+    let mut entries = IndexMap::new();
+    entries.insert("lat".to_string(), Value::Number(lat));
+    entries.insert("lon".to_string(), Value::Number(lon));
+    Value::object(entries)
+}
+
+fn number_field(value: &Value, name: &str) -> Option<f64> {
+    match value {
+        Value::Object(obj) => match obj.borrow().get(name) {
+            Some(Value::Number(n)) => Some(*n),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Arrays e objetos sendo percorridos agora; reencontrar um deles é um ciclo
+type Seen = Vec<*const ()>;
+
+// Percorre `value` com `f`, marcando-o como visitado se for um array ou objeto
+fn visit<T, E>(
+    value: &Value,
+    seen: &mut Seen,
+    cyclic: impl FnOnce() -> E,
+    f: impl FnOnce(&mut Seen) -> Result<T, E>,
+) -> Result<T, E> {
+    let ptr = match value {
+        Value::Array(items) => Rc::as_ptr(items) as *const (),
+        Value::Object(obj) => Rc::as_ptr(obj) as *const (),
+        _ => return f(seen),
+    };
+    if seen.contains(&ptr) {
+        return Err(cyclic());
+    }
+    seen.push(ptr);
+    let result = f(seen);
+    seen.pop();
+    result
+}
+
+fn cyclic(function: &str) -> AdgError {
+    AdgError::type_error(format!("geo.{}: estrutura cíclica", function))
+}
+
+fn check_range(lat: f64, lon: f64, function: &str) -> Result<(f64, f64), AdgError> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+        return Err(AdgError::type_error(format!(
+            "geo.{}: coordenada fora do intervalo (lat {}, lon {})", function, lat, lon,
+        )));
+    }
+    Ok((lat, lon))
+}
+
+// Ponto como `(lat, lon)` em graus: aceita `{ lat, lon }` ou um Point do GeoJSON
+fn to_point(value: &Value, function: &str) -> Result<(f64, f64), AdgError> {
+    if let (Some(lat), Some(lon)) = (number_field(value, "lat"), number_field(value, "lon")) {
+        return check_range(lat, lon, function);
+    }
+    if let Value::Object(obj) = value
+        && obj.borrow().get("type") == Some(&Value::String("Point".to_string()))
+        && let Some(coordinates) = obj.borrow().get("coordinates")
+        && let Some((lon, lat)) = position(coordinates)
+    {
+        return check_range(lat, lon, function);
+    }
+    Err(AdgError::type_error(format!(
+        "geo.{}: esperado um ponto {{ lat, lon }}, recebeu {}", function, value.as_string(),
+    )))
+}
+
+// Posição do GeoJSON: `[lon, lat, ...]`
+fn position(value: &Value) -> Option<(f64, f64)> {
+    match value {
+        Value::Array(items) => match items.borrow().as_slice() {
+            [Value::Number(lon), Value::Number(lat), ..] => Some((*lon, *lat)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn point(args: Vec<Value>) -> Result<Value, AdgError> {
+    expect_args(&args, 2, "point")?;
+    let (lat, lon) = check_range(args[0].as_number()?, args[1].as_number()?, "point")?;
+    Ok(point_value(lat, lon))
+}
+
+/// Distância sobre a esfera, em quilômetros.
+fn haversine_km((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+    let h = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().min(1.0).asin()
+}
+
+fn haversine(args: Vec<Value>) -> Result<Value, AdgError> {
+    expect_args(&args, 2, "haversine")?;
+    let (a, b) = (to_point(&args[0], "haversine")?, to_point(&args[1], "haversine")?);
+    Ok(Value::Number(haversine_km(a, b)))
+}
+
+/// Distância sobre o elipsoide WGS-84 (fórmula inversa de Vincenty), em
+/// quilômetros; `None` se a iteração não convergir (pontos quase antípodas).
+fn vincenty_km((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> Option<f64> {
+    let b = (1.0 - WGS84_F) * WGS84_A;
+    let l = (lon2 - lon1).to_radians();
+    let u1 = ((1.0 - WGS84_F) * lat1.to_radians().tan()).atan();
+    let u2 = ((1.0 - WGS84_F) * lat2.to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+
+    let mut lambda = l;
+    for _ in 0..200 {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+            + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+            .sqrt();
+        if sin_sigma == 0.0 {
+            return Some(0.0); // mesmo ponto
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+        // Sobre o equador, cos²α = 0
+        let cos_2sigma_m = if cos2_alpha == 0.0 { 0.0 } else { cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha };
+        let c = WGS84_F / 16.0 * cos2_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos2_alpha));
+
+        let previous = lambda;
+        lambda = l + (1.0 - c) * WGS84_F * sin_alpha
+            * (sigma + c * sin_sigma * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+
+        if (lambda - previous).abs() < 1e-12 {
+            let u_sq = cos2_alpha * (WGS84_A.powi(2) - b.powi(2)) / b.powi(2);
+            let a_coef = 1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+            let b_coef = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+            let delta_sigma = b_coef * sin_sigma * (cos_2sigma_m + b_coef / 4.0
+                * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                    - b_coef / 6.0 * cos_2sigma_m * (-3.0 + 4.0 * sin_sigma.powi(2)) * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+            return Some(b * a_coef * (sigma - delta_sigma) / 1000.0);
+        }
+    }
+    None
+}
+
+fn vincenty(args: Vec<Value>) -> Result<Value, AdgError> {
+    expect_args(&args, 2, "vincenty")?;
+    let (a, b) = (to_point(&args[0], "vincenty")?, to_point(&args[1], "vincenty")?);
+    vincenty_km(a, b)
+        .map(Value::Number)
+        .ok_or_else(|| AdgError::type_error("geo.vincenty: o cálculo não convergiu (pontos quase antípodas); use geo.haversine"))
+}
+
+/// Rumo inicial de `a` para `b`, em graus a partir do norte (0 a 360).
+fn bearing_deg((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lambda = (lon2 - lon1).to_radians();
+    let y = d_lambda.sin() * phi2.cos();
+    let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * d_lambda.cos();
+    (y.atan2(x).to_degrees() + 360.0) % 360.0
+}
+
+fn bearing(args: Vec<Value>) -> Result<Value, AdgError> {
+    expect_args(&args, 2, "bearing")?;
+    let (a, b) = (to_point(&args[0], "bearing")?, to_point(&args[1], "bearing")?);
+    Ok(Value::Number(bearing_deg(a, b)))
+}
+
+// Ponto alcançado saindo de `start` no rumo dado, após `km` quilômetros
+fn destination(args: Vec<Value>) -> Result<Value, AdgError> {
+    expect_args(&args, 3, "destination")?;
+    let (lat, lon) = to_point(&args[0], "destination")?;
+    let theta = args[1].as_number()?.to_radians();
+    let delta = args[2].as_number()? / EARTH_RADIUS_KM;
+
+    let phi1 = lat.to_radians();
+    let phi2 = (phi1.sin() * delta.cos() + phi1.cos() * delta.sin() * theta.cos()).asin();
+    let lambda2 = lon.to_radians()
+        + (theta.sin() * delta.sin() * phi1.cos()).atan2(delta.cos() - phi1.sin() * phi2.sin());
+    // Normaliza a longitude para [-180, 180)
+    let lon2 = (lambda2.to_degrees() + 540.0) % 360.0 - 180.0;
+    Ok(point_value(phi2.to_degrees(), lon2))
+}
+
+// Todos os pontos (lat, lon) de uma lista de pontos ou de um valor GeoJSON
+fn collect_points(value: &Value, out: &mut Vec<(f64, f64)>, seen: &mut Seen) -> Result<(), AdgError> {
+    if let Ok(point) = to_point(value, "bbox") {
+        out.push(point);
+        return Ok(());
+    }
+    visit(value, seen, || cyclic("bbox"), |seen| {
+        match value {
+            Value::Array(items) => {
+                for item in items.borrow().iter() {
+                    collect_points(item, out, seen)?;
+                }
+            }
+            Value::Object(obj) => {
+                let obj = obj.borrow();
+                for key in ["features", "geometries", "geometry"] {
+                    if let Some(inner) = obj.get(key) {
+                        collect_points(inner, out, seen)?;
+                    }
+                }
+                if let Some(coordinates) = obj.get("coordinates") {
+                    collect_positions(coordinates, out, seen, "bbox")?;
+                }
+            }
+            Value::Null => {} // Feature sem geometria
+            other => return Err(AdgError::type_error(format!(
+                "geo.bbox: esperado pontos ou GeoJSON, recebeu {}", other.type_name(),
+            ))),
+        }
+        Ok(())
+    })
+}
+
+fn collect_positions(
+    value: &Value,
+    out: &mut Vec<(f64, f64)>,
+    seen: &mut Seen,
+    function: &str,
+) -> Result<(), AdgError> {
+    if let Some((lon, lat)) = position(value) {
+        out.push((lat, lon));
+        return Ok(());
+    }
+    let Value::Array(items) = value else { return Ok(()) };
+    visit(value, seen, || cyclic(function), |seen| {
+        items.borrow().iter().try_for_each(|item| collect_positions(item, out, seen, function))
+    })
+}
+
+fn bbox(args: Vec<Value>) -> Result<Value, AdgError> {
+    expect_args(&args, 1, "bbox")?;
+    let mut points = vec![];
+    collect_points(&args[0], &mut points, &mut vec![])?;
+    if points.is_empty() {
+        return Err(AdgError::type_error("geo.bbox: nenhum ponto informado"));
+    }
+
+    let (mut min_lat, mut min_lon) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_lat, mut max_lon) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for (lat, lon) in points {
+        min_lat = min_lat.min(lat);
+        min_lon = min_lon.min(lon);
+        max_lat = max_lat.max(lat);
+        max_lon = max_lon.max(lon);
+    }
+
+    let mut entries = IndexMap::new();
+    entries.insert("minLat".to_string(), Value::Number(min_lat));
+    entries.insert("minLon".to_string(), Value::Number(min_lon));
+    entries.insert("maxLat".to_string(), Value::Number(max_lat));
+    entries.insert("maxLon".to_string(), Value::Number(max_lon));
+    Ok(Value::object(entries))
+}
+
+type Ring = Vec<(f64, f64)>;
+
+// Polígonos (cada um com seus anéis: o contorno e os buracos) de uma lista de
+// pontos ou de um Polygon/MultiPolygon do GeoJSON (também dentro de uma Feature)
+fn polygons(value: &Value, seen: &mut Seen) -> Result<Vec<Vec<Ring>>, AdgError> {
+    let invalid = || AdgError::type_error(
+        "geo.pointInPolygon: esperado uma lista de pontos ou um Polygon/MultiPolygon do GeoJSON",
+    );
+    let rings_of = |value: &Value, seen: &mut Seen| -> Result<Vec<Ring>, AdgError> {
+        let Value::Array(rings) = value else { return Err(invalid()) };
+        rings.borrow().iter()
+            .map(|ring| {
+                let mut points = vec![];
+                collect_positions(ring, &mut points, seen, "pointInPolygon")?;
+                Ok(points)
+            })
+            .collect()
+    };
+
+    match value {
+        Value::Array(items) => {
+            let ring = items.borrow().iter()
+                .map(|item| to_point(item, "pointInPolygon"))
+                .collect::<Result<Ring, _>>()?;
+            Ok(vec![vec![ring]])
+        }
+        Value::Object(obj) => {
+            let obj = obj.borrow();
+            let kind = obj.get("type").map(Value::as_string).unwrap_or_default();
+            match (kind.as_str(), obj.get("coordinates"), obj.get("geometry")) {
+                ("Feature", _, Some(geometry)) => {
+                    visit(value, seen, || cyclic("pointInPolygon"), |seen| polygons(geometry, seen))
+                }
+                ("Polygon", Some(coordinates), _) => Ok(vec![rings_of(coordinates, seen)?]),
+                ("MultiPolygon", Some(Value::Array(list)), _) => {
+                    list.borrow().iter().map(|polygon| rings_of(polygon, seen)).collect()
+                }
+                _ => Err(invalid()),
+            }
+        }
+        _ => Err(invalid()),
+    }
+}
+
+// Regra par-ímpar: contando todos os anéis, um ponto num buraco fica de fora.
+// Lat/lon são tratados como coordenadas planas, o que basta para áreas pequenas.
+fn inside((lat, lon): (f64, f64), rings: &[Ring]) -> bool {
+    let mut inside = false;
+    for ring in rings {
+        if ring.is_empty() {
+            continue;
+        }
+        let mut j = ring.len() - 1;
+        for i in 0..ring.len() {
+            let (lat_i, lon_i) = ring[i];
+            let (lat_j, lon_j) = ring[j];
+            if (lat_i > lat) != (lat_j > lat)
+                && lon < (lon_j - lon_i) * (lat - lat_i) / (lat_j - lat_i) + lon_i
+            {
+                inside = !inside;
+            }
+            j = i;
+        }
+    }
+    inside
+}
+
+fn point_in_polygon(args: Vec<Value>) -> Result<Value, AdgError> {
+    expect_args(&args, 2, "pointInPolygon")?;
+    let point = to_point(&args[0], "pointInPolygon")?;
+    let found = polygons(&args[1], &mut vec![])?.iter().any(|rings| inside(point, rings));
+    Ok(Value::Boolean(found))
+}
+
+// 23.5504 (lat) vira 23°33'01.44"N; os segundos têm duas casas decimais
+fn format_dms(value: f64, positive: char, negative: char) -> String {
+    let hemisphere = if value < 0.0 { negative } else { positive };
+    // Em centésimos de segundo, para o arredondamento não gerar 60"
+    let total = (value.abs() * 360000.0).round() as u64;
+    let (degrees, rest) = (total / 360000, total % 360000);
+    let (minutes, hundredths) = (rest / 6000, rest % 6000);
+    format!("{}°{:02}'{:02}.{:02}\"{}", degrees, minutes, hundredths / 100, hundredths % 100, hemisphere)
+}
+
+fn to_dms(args: Vec<Value>) -> Result<Value, AdgError> {
+    match args.as_slice() {
+        [value] => {
+            let (lat, lon) = to_point(value, "toDMS")?;
+            Ok(Value::String(format!("{} {}", format_dms(lat, 'N', 'S'), format_dms(lon, 'E', 'W'))))
+        }
+        [value, axis] => {
+            let value = value.as_number()?;
+            match axis.as_string().as_str() {
+                "lat" => Ok(Value::String(format_dms(value, 'N', 'S'))),
+                "lon" => Ok(Value::String(format_dms(value, 'E', 'W'))),
+                other => Err(AdgError::type_error(format!("geo.toDMS: eixo deve ser \"lat\" ou \"lon\", recebeu \"{}\"", other))),
+            }
+        }
+        _ => Err(AdgError::arity_error("geo.toDMS espera 1 ou 2 argumentos")),
+    }
+}
+
+/// Uma coordenada em DMS (ou graus decimais): `23°33'01.44"S`, `23 33 1.44 S`,
+/// `-23.55`. O hemisfério pode ser N/S/E/W, ou L/O em português.
+fn parse_coordinate(text: &str) -> Option<(f64, Option<bool>)> {
+    let text = text.trim();
+    let (text, hemisphere) = match text.chars().last()?.to_ascii_uppercase() {
+        c @ ('N' | 'S' | 'E' | 'W' | 'L' | 'O') => (&text[..text.len() - 1], Some(c)),
+        _ => (text, None),
+    };
+    let (negative, text) = match text.trim_start().strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+
+    let parts: Vec<f64> = text
+        .split(|c: char| c.is_whitespace() || matches!(c, '°' | 'º' | '\'' | '"' | '′' | '″'))
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<f64>().ok().filter(|n| *n >= 0.0))
+        .collect::<Option<_>>()?;
+    let value = match parts.as_slice() {
+        [d] => *d,
+        [d, m] if *m < 60.0 => d + m / 60.0,
+        [d, m, s] if *m < 60.0 && *s < 60.0 => d + m / 60.0 + s / 3600.0,
+        _ => return None,
+    };
+
+    let south_or_west = matches!(hemisphere, Some('S' | 'W' | 'O'));
+    let is_lat = hemisphere.map(|c| matches!(c, 'N' | 'S'));
+    Some((if negative != south_or_west { -value } else { value }, is_lat))
+}
+
+fn from_dms(args: Vec<Value>) -> Result<Value, AdgError> {
+    expect_args(&args, 1, "fromDMS")?;
+    let text = args[0].as_string();
+    let invalid = || AdgError::type_error(format!("geo.fromDMS: coordenada inválida \"{}\"", text));
+
+    // Duas coordenadas: separadas por vírgula, ou cada uma terminando no hemisfério
+    let chunks: Vec<&str> = if text.contains(',') {
+        text.split(',').collect()
+    } else {
+        text.split_inclusive(|c: char| "NSEWLOnsewlo".contains(c))
+            .filter(|chunk| !chunk.trim().is_empty())
+            .collect()
+    };
+
+    match chunks.as_slice() {
+        [single] => parse_coordinate(single).map(|(value, _)| Value::Number(value)).ok_or_else(invalid),
+        [first, second] => {
+            let (a, a_is_lat) = parse_coordinate(first).ok_or_else(invalid)?;
+            let (b, b_is_lat) = parse_coordinate(second).ok_or_else(invalid)?;
+            // Com hemisférios, a ordem não importa: `46°W 23°S` também vale
+            let (lat, lon) = if a_is_lat == Some(false) || b_is_lat == Some(true) { (b, a) } else { (a, b) };
+            let (lat, lon) = check_range(lat, lon, "fromDMS")?;
+            Ok(point_value(lat, lon))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn from_json(value: serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Boolean(b),
+        serde_json::Value::Number(n) => Value::Number(n.as_f64().unwrap_or(f64::NAN)),
+        serde_json::Value::String(s) => Value::String(s),
+        serde_json::Value::Array(items) => Value::array(items.into_iter().map(from_json).collect()),
+        serde_json::Value::Object(entries) => {
+            Value::object(entries.into_iter().map(|(key, value)| (key, from_json(value))).collect())
+        }
+    }
+}
+
+fn to_json(value: &Value, seen: &mut Seen) -> Result<serde_json::Value, String> {
+    visit(value, seen, || "estrutura cíclica".to_string(), |seen| Ok(match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Boolean(b) => serde_json::Value::Bool(*b),
+        // Inteiros saem sem `.0`
+        Value::Number(n) if n.fract() == 0.0 && n.abs() < 9007199254740992.0 => serde_json::Value::from(*n as i64),
+        Value::Number(n) => serde_json::Number::from_f64(*n)
+            .map(serde_json::Value::Number)
+            .ok_or_else(|| format!("número inválido em JSON: {}", n))?,
+        Value::String(s) => serde_json::Value::String(s.clone()),
+        Value::Array(items) => serde_json::Value::Array(
+            items.borrow().iter().map(|item| to_json(item, seen)).collect::<Result<_, _>>()?,
+        ),
+        Value::Object(obj) => serde_json::Value::Object(
+            obj.borrow().iter()
+                .map(|(key, value)| Ok((key.clone(), to_json(value, seen)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        other => return Err(format!("{} não pode ser convertido para JSON", other.type_name())),
+    }))
+}
+
+// Confere a estrutura exigida pela RFC 7946
+fn validate_geojson(value: &serde_json::Value) -> Result<(), String> {
+    use serde_json::Value as Json;
+
+    fn is_position(value: &Json) -> bool {
+        matches!(value, Json::Array(items) if items.len() >= 2 && items.iter().all(Json::is_number))
+    }
+    fn positions(value: &Json, min: usize) -> bool {
+        matches!(value, Json::Array(items) if items.len() >= min && items.iter().all(is_position))
+    }
+    fn ring(value: &Json) -> bool {
+        positions(value, 4) && value.get(0) == value.as_array().and_then(|items| items.last())
+    }
+    fn list(value: &Json, item: impl Fn(&Json) -> bool) -> bool {
+        matches!(value, Json::Array(items) if items.iter().all(item))
+    }
+
+    let kind = value.get("type").and_then(Json::as_str).ok_or("objeto sem \"type\"")?;
+    let coordinates = value.get("coordinates").unwrap_or(&Json::Null);
+    let valid = match kind {
+        "Point" => is_position(coordinates),
+        "MultiPoint" => positions(coordinates, 0),
+        "LineString" => positions(coordinates, 2),
+        "MultiLineString" => list(coordinates, |line| positions(line, 2)),
+        "Polygon" => list(coordinates, ring),
+        "MultiPolygon" => list(coordinates, |polygon| list(polygon, ring)),
+        "GeometryCollection" => {
+            let geometries = value.get("geometries").and_then(Json::as_array).ok_or("GeometryCollection sem \"geometries\"")?;
+            return geometries.iter().try_for_each(validate_geojson);
+        }
+        "Feature" => {
+            if !matches!(value.get("properties"), None | Some(Json::Null | Json::Object(_))) {
+                return Err("\"properties\" de uma Feature deve ser um objeto ou null".to_string());
+            }
+            return match value.get("geometry") {
+                Some(Json::Null) => Ok(()),
+                Some(geometry) => validate_geojson(geometry),
+                None => Err("Feature sem \"geometry\"".to_string()),
+            };
+        }
+        "FeatureCollection" => {
+            let features = value.get("features").and_then(Json::as_array).ok_or("FeatureCollection sem \"features\"")?;
+            return features.iter().try_for_each(|feature| match feature.get("type").and_then(Json::as_str) {
+                Some("Feature") => validate_geojson(feature),
+                _ => Err("FeatureCollection só pode conter Features".to_string()),
+            });
+        }
+        other => return Err(format!("tipo desconhecido \"{}\"", other)),
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        // Polígonos exigem anéis fechados com pelo menos 4 posições
+        Err(format!("\"coordinates\" inválidas para {}", kind))
+    }
+}
+
+fn parse_geojson(args: Vec<Value>) -> Result<Value, AdgError> {
+    expect_args(&args, 1, "parseGeoJSON")?;
+    let json: serde_json::Value = serde_json::from_str(&args[0].as_string())
+        .map_err(|err| AdgError::type_error(format!("geo.parseGeoJSON: JSON inválido: {}", err)))?;
+    validate_geojson(&json)
+        .map_err(|err| AdgError::type_error(format!("geo.parseGeoJSON: GeoJSON inválido: {}", err)))?;
+    Ok(from_json(json))
+}
+
+fn stringify_geojson(args: Vec<Value>) -> Result<Value, AdgError> {
+    expect_args(&args, 1, "stringifyGeoJSON")?;
+    let error = |err: String| AdgError::type_error(format!("geo.stringifyGeoJSON: {}", err));
+    let json = to_json(&args[0], &mut vec![]).map_err(error)?;
+    validate_geojson(&json).map_err(|err| error(format!("GeoJSON inválido: {}", err)))?;
+    Ok(Value::String(json.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(name: &str, args: Vec<Value>) -> Result<Value, AdgError> {
+        match get_geo_module().get(name) {
+            Some(Value::NativeFunction(f)) => f(args),
+            _ => panic!("geo.{} not found", name),
+        }
+    }
+
+    fn number(name: &str, args: Vec<Value>) -> f64 {
+        call(name, args).and_then(|value| value.as_number()).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn dms(text: &str) -> Value {
+        call("fromDMS", vec![Value::String(text.to_string())]).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    fn text(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    #[test]
+    fn vincenty_matches_the_flinders_peak_reference() {
+        // Exemplo clássico do artigo de Vincenty: Flinders Peak → Buninyong, 54 972,271 m
+        let flinders = dms("37°57'03.72030\"S 144°25'29.52440\"E");
+        let buninyong = dms("37°39'10.15610\"S 143°55'35.38390\"E");
+
+        let km = number("vincenty", vec![flinders.clone(), buninyong.clone()]);
+        assert!((km - 54.972271).abs() < 1e-6, "{}", km);
+
+        // A esfera erra por pouco nessa distância
+        let spherical = number("haversine", vec![flinders, buninyong]);
+        assert!((spherical - km).abs() < 0.2, "{}", spherical);
+    }
+
+    #[test]
+    fn haversine_and_bearing_match_the_aviation_formulary() {
+        // LAX (33°57'N 118°24'W) → JFK (40°38'N 73°47'W): 0,623585 rad, rumo inicial 65,89°
+        let lax = dms("33°57'N 118°24'W");
+        let jfk = dms("40°38'N, 73°47'W");
+
+        let km = number("haversine", vec![lax.clone(), jfk.clone()]);
+        assert!((km / EARTH_RADIUS_KM - 0.623585).abs() < 1e-6, "{}", km);
+        let course = number("bearing", vec![lax.clone(), jfk.clone()]);
+        assert!((course - 65.89).abs() < 0.01, "{}", course);
+
+        // Andar a distância no rumo inicial leva de volta a JFK
+        let arrival = call("destination", vec![lax, Value::Number(course), Value::Number(km)]).unwrap();
+        assert!(number("haversine", vec![arrival, jfk]) < 1e-6);
+
+        let same = dms("-23.55, -46.63");
+        assert_eq!(number("vincenty", vec![same.clone(), same.clone()]), 0.0);
+        assert_eq!(number("haversine", vec![same.clone(), same]), 0.0);
+    }
+
+    #[test]
+    fn dms_round_trips() {
+        let se = call("point", vec![Value::Number(-23.55052), Value::Number(-46.633308)]).unwrap();
+        assert_eq!(call("toDMS", vec![se]).unwrap().as_string(), "23°33'01.87\"S 46°37'59.91\"W");
+        assert_eq!(call("toDMS", vec![Value::Number(10.999999), text("lon")]).unwrap().as_string(), "11°00'00.00\"E");
+
+        let text_dms = "23°33'01.87\"S 46°37'59.91\"W";
+        assert_eq!(call("toDMS", vec![dms(text_dms)]).unwrap().as_string(), text_dms);
+        // Ordem trocada, hemisférios em português e espaços no lugar dos símbolos
+        assert_eq!(call("toDMS", vec![dms("46 37 59.91 O 23 33 1.87 S")]).unwrap().as_string(), text_dms);
+        assert_eq!(dms("-12.5").as_number().unwrap(), -12.5);
+        assert_eq!(dms("12°30'S").as_number().unwrap(), -12.5);
+
+        for invalid in ["abc", "10°61'N", "1, 2, 3", "95°N 10°E"] {
+            assert!(call("fromDMS", vec![text(invalid)]).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn bounding_boxes_and_point_in_polygon() {
+        let square = Value::array(vec![
+            dms("0, 0"), dms("0, 10"), dms("10, 10"), dms("10, 0"),
+        ]);
+        assert_eq!(call("bbox", vec![square.clone()]).unwrap().as_string(), "{ minLat: 0, minLon: 0, maxLat: 10, maxLon: 10 }");
+        assert_eq!(call("pointInPolygon", vec![dms("5, 5"), square.clone()]).unwrap(), Value::Boolean(true));
+        assert_eq!(call("pointInPolygon", vec![dms("5, 15"), square]).unwrap(), Value::Boolean(false));
+
+        // Polígono com buraco, em GeoJSON ([lon, lat])
+        let donut = call("parseGeoJSON", vec![text(r#"{
+            "type": "Feature",
+            "properties": { "nome": "anel" },
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [
+                    [[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]],
+                    [[4, 4], [6, 4], [6, 6], [4, 6], [4, 4]]
+                ]
+            }
+        }"#)]).unwrap();
+        assert_eq!(call("pointInPolygon", vec![dms("2, 2"), donut.clone()]).unwrap(), Value::Boolean(true));
+        assert_eq!(call("pointInPolygon", vec![dms("5, 5"), donut.clone()]).unwrap(), Value::Boolean(false));
+        assert_eq!(call("bbox", vec![donut]).unwrap().as_string(), "{ minLat: 0, minLon: 0, maxLat: 10, maxLon: 10 }");
+
+        assert!(call("bbox", vec![Value::array(vec![])]).is_err());
+        assert!(call("point", vec![Value::Number(91.0), Value::Number(0.0)]).is_err());
+    }
+
+    #[test]
+    fn geojson_parses_into_values_and_back() {
+        let source = r#"{"type":"FeatureCollection","features":[{"type":"Feature","properties":{"id":1,"peso":2.5},"geometry":{"type":"Point","coordinates":[-46.63,-23.55]}}]}"#;
+        let value = call("parseGeoJSON", vec![text(source)]).unwrap();
+
+        let Value::Object(collection) = &value else { panic!("expected object") };
+        let features = collection.borrow().get("features").cloned().unwrap();
+        let Value::Array(features) = features else { panic!("expected array") };
+        let point = features.borrow()[0].get_member("geometry").unwrap();
+        // Um Point do GeoJSON serve como ponto
+        assert!(number("haversine", vec![point, dms("-23.55, -46.63")]) < 1e-9);
+
+        assert_eq!(call("stringifyGeoJSON", vec![value]).unwrap().as_string(), source);
+
+        let invalid = [
+            "{",
+            r#"{"type":"Circle","coordinates":[0,0]}"#,
+            r#"{"type":"Point","coordinates":[0]}"#,
+            r#"{"type":"Polygon","coordinates":[[[0,0],[1,0],[1,1],[0,1]]]}"#,
+            r#"{"type":"Feature","properties":1,"geometry":null}"#,
+        ];
+        for source in invalid {
+            assert!(call("parseGeoJSON", vec![text(source)]).is_err(), "{}", source);
+        }
+        assert!(call("stringifyGeoJSON", vec![dms("1, 2")]).is_err());
+    }
+
+    #[test]
+    fn cyclic_structures_are_errors() {
+        let feature = call("parseGeoJSON", vec![text(r#"{"type":"Feature","properties":{},"geometry":null}"#)]).unwrap();
+        let Value::Object(obj) = &feature else { panic!("expected object") };
+        obj.borrow_mut().insert("geometry".to_string(), feature.clone());
+
+        let ring = Value::array(vec![]);
+        let Value::Array(items) = &ring else { panic!("expected array") };
+        items.borrow_mut().push(ring.clone());
+        let polygon = call("parseGeoJSON", vec![text(r#"{"type":"Polygon","coordinates":[]}"#)]).unwrap();
+        let Value::Object(obj) = &polygon else { panic!("expected object") };
+        obj.borrow_mut().insert("coordinates".to_string(), Value::array(vec![ring]));
+
+        let cases = [
+            ("stringifyGeoJSON", vec![feature.clone()]),
+            ("bbox", vec![feature.clone()]),
+            ("bbox", vec![polygon.clone()]),
+            ("pointInPolygon", vec![dms("0, 0"), feature]),
+            ("pointInPolygon", vec![dms("0, 0"), polygon]),
+        ];
+        for (function, args) in cases {
+            let err = call(function, args).unwrap_err();
+            assert_eq!(err.message(), format!("geo.{}: estrutura cíclica", function));
+        }
+    }
+}
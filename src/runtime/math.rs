@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use crate::error::AdgError;
 use crate::interpreter::Value;
 
 pub fn get_math_module() -> HashMap<String, Value> {
@@ -6,20 +7,20 @@ pub fn get_math_module() -> HashMap<String, Value> {
 
     map.insert("sqrt".to_string(), Value::NativeFunction(|args| {
         if args.len() != 1 {
-            panic!("math.sqrt espera 1 argumento");
+            return Err(AdgError::arity_error("math.sqrt espera 1 argumento"));
         }
-        Value::Number(args[0].as_number().sqrt())
+        Ok(Value::Number(args[0].as_number()?.sqrt()))
     }));
 
     map.insert("pow".to_string(), Value::NativeFunction(|args| {
         if args.len() != 2 {
-            panic!("math.pow espera 2 argumentos");
+            return Err(AdgError::arity_error("math.pow espera 2 argumentos"));
         }
-        Value::Number(args[0].as_number().powf(args[1].as_number()))
+        Ok(Value::Number(args[0].as_number()?.powf(args[1].as_number()?)))
     }));
 
     map.insert("random".to_string(), Value::NativeFunction(|_| {
-        Value::Number(rand::random::<f64>())
+        Ok(Value::Number(rand::random::<f64>()))
     }));
 
     map
@@ -1,3 +1,5 @@
 pub mod math;
 pub mod filebox;
-pub mod style;
\ No newline at end of file
+pub mod style;
+pub mod geo;
+pub mod qr;
\ No newline at end of file
@@ -0,0 +1,926 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use qrcode::bits::Bits;
+use qrcode::{Color, EcLevel, QrCode, Version};
+use crate::error::AdgError;
+use crate::interpreter::Value;
+
+pub fn get_qr_module() -> HashMap<String, Value> {
+    let mut map = HashMap::new();
+
+    map.insert("svg".to_string(), Value::NativeFunction(svg));
+    map.insert("png".to_string(), Value::NativeFunction(png));
+    map.insert("unicode".to_string(), Value::NativeFunction(unicode));
+
+    map
+}
+
+/// Código pronto para desenhar, com `true` nos módulos escuros.
+enum Symbol {
+    /// QR code: linhas de módulos
+    Matrix(Vec<Vec<bool>>),
+    /// Código de barras: uma única linha de barras e espaços
+    Linear(Vec<bool>),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Qr,
+    Code128,
+    Ean13,
+}
+
+struct Options {
+    kind: Kind,
+    ec: EcLevel,
+    // Largura em pixels (svg/png)
+    size: Option<usize>,
+    // Altura das barras: pixels no svg/png, linhas no unicode
+    height: Option<usize>,
+    // Zona de silêncio, em módulos
+    margin: usize,
+    invert: bool,
+}
+
+// Limites das opções: um valor absurdo vira erro em vez de gigabytes de imagem
+const MAX_SIZE: usize = 10_000;
+const MAX_MARGIN: usize = 100;
+const MAX_PIXELS: usize = 40_000_000;
+
+fn options(value: Option<&Value>, function: &str) -> Result<Options, AdgError> {
+    let mut options = Options { kind: Kind::Qr, ec: EcLevel::M, size: None, height: None, margin: 4, invert: false };
+    let mut margin = None;
+
+    let entries = match value {
+        None => return Ok(options),
+        Some(Value::Object(obj)) => obj.borrow().clone(),
+        Some(other) => return Err(AdgError::type_error(format!(
+            "qr.{}: as opções devem ser um objeto, recebeu {}", function, other.type_name(),
+        ))),
+    };
+
+    let integer = |value: &Value, name: &str, min: usize, max: usize| -> Result<usize, AdgError> {
+        let n = value.as_number()?;
+        if n < min as f64 || n > max as f64 || n.fract() != 0.0 {
+            return Err(AdgError::type_error(format!(
+                "qr.{}: \"{}\" deve ser um inteiro entre {} e {}", function, name, min, max,
+            )));
+        }
+        Ok(n as usize)
+    };
+
+    for (key, value) in &entries {
+        match key.as_str() {
+            "type" => options.kind = match value.as_string().as_str() {
+                "qr" => Kind::Qr,
+                "code128" => Kind::Code128,
+                "ean13" => Kind::Ean13,
+                other => return Err(AdgError::type_error(format!(
+                    "qr.{}: tipo desconhecido \"{}\" (use \"qr\", \"code128\" ou \"ean13\")", function, other,
+                ))),
+            },
+            "ec" => options.ec = match value.as_string().as_str() {
+                "L" => EcLevel::L,
+                "M" => EcLevel::M,
+                "Q" => EcLevel::Q,
+                "H" => EcLevel::H,
+                other => return Err(AdgError::type_error(format!(
+                    "qr.{}: nível de correção \"{}\" inválido (use \"L\", \"M\", \"Q\" ou \"H\")", function, other,
+                ))),
+            },
+            "size" => options.size = Some(integer(value, "size", 1, MAX_SIZE)?),
+            "height" => options.height = Some(integer(value, "height", 1, MAX_SIZE)?),
+            "margin" => margin = Some(integer(value, "margin", 0, MAX_MARGIN)?),
+            "invert" => options.invert = value.as_bool()?,
+            other => return Err(AdgError::type_error(format!("qr.{}: opção desconhecida \"{}\"", function, other))),
+        }
+    }
+
+    // EAN-13 pede 11 módulos de silêncio à esquerda; Code128, 10
+    options.margin = margin.unwrap_or(match options.kind {
+        Kind::Qr => 4,
+        Kind::Code128 => 10,
+        Kind::Ean13 => 11,
+    });
+    Ok(options)
+}
+
+fn encode(text: &str, options: &Options) -> Result<Symbol, AdgError> {
+    match options.kind {
+        Kind::Qr => encode_qr(text, options.ec).map(Symbol::Matrix),
+        Kind::Code128 => encode_code128(text).map(Symbol::Linear),
+        Kind::Ean13 => encode_ean13(text).map(Symbol::Linear),
+    }
+}
+
+fn encode_qr(text: &str, ec: EcLevel) -> Result<Vec<Vec<bool>>, AdgError> {
+    let code = if text.is_ascii() {
+        QrCode::with_error_correction_level(text, ec).ok()
+    } else {
+        // O modo kanji do `qrcode` confundiria bytes de UTF-8 com Shift JIS, então
+        // fora do ASCII tudo vai em modo byte, na menor versão que couber
+        (1..=40).find_map(|version| {
+            let mut bits = Bits::new(Version::Normal(version));
+            bits.push_byte_data(text.as_bytes()).ok()?;
+            bits.push_terminator(ec).ok()?;
+            QrCode::with_bits(bits, ec).ok()
+        })
+    };
+
+    let code = code.ok_or_else(|| AdgError::type_error(format!(
+        "qr: texto longo demais para um QR code ({} bytes com correção {:?})", text.len(), ec,
+    )))?;
+    Ok(code.to_colors()
+        .chunks(code.width())
+        .map(|row| row.iter().map(|color| *color == Color::Dark).collect())
+        .collect())
+}
+
+// Larguras de barra/espaço/barra/... de cada símbolo do Code128, pelo valor
+const CODE128: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212", "221213",
+    "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221", "223211", "221132",
+    "221231", "213212", "223112", "312131", "311222", "321122", "321221", "312212", "322112", "322211",
+    "212123", "212321", "232121", "111323", "131123", "131321", "112313", "132113", "132311", "211313",
+    "231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121", "313121", "211331",
+    "231131", "213113", "213311", "213131", "311123", "311321", "331121", "312113", "312311", "332111",
+    "314111", "221411", "431111", "111224", "111422", "121124", "121421", "141122", "141221", "112214",
+    "112412", "122114", "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111",
+    "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311", "113141",
+    "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+const CODE128_CODE_C: usize = 99;
+const CODE128_CODE_B: usize = 100;
+const CODE128_START_B: usize = 104;
+const CODE128_START_C: usize = 105;
+const CODE128_STOP: usize = 106;
+
+#[derive(Clone, Copy, PartialEq)]
+enum CodeSet {
+    B,
+    C,
+}
+
+// Abre o conjunto pedido (o primeiro vira o símbolo de início)
+fn switch_set(values: &mut Vec<usize>, current: &mut Option<CodeSet>, wanted: CodeSet) {
+    match (*current, wanted) {
+        (Some(set), _) if set == wanted => return,
+        (None, CodeSet::B) => values.push(CODE128_START_B),
+        (None, CodeSet::C) => values.push(CODE128_START_C),
+        (Some(_), CodeSet::B) => values.push(CODE128_CODE_B),
+        (Some(_), CodeSet::C) => values.push(CODE128_CODE_C),
+    }
+    *current = Some(wanted);
+}
+
+/// Code128 com os conjuntos B (ASCII imprimível) e C (pares de dígitos).
+fn encode_code128(text: &str) -> Result<Vec<bool>, AdgError> {
+    if text.is_empty() || !text.bytes().all(|b| (32..127).contains(&b)) {
+        return Err(AdgError::type_error("qr: Code128 aceita só texto ASCII imprimível, não vazio"));
+    }
+
+    let bytes = text.as_bytes();
+    let mut values = vec![];
+    let mut set = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        // Trocar para o conjunto C custa um símbolo; só compensa com dígitos suficientes
+        let run = bytes[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+        let threshold = match (i == 0, i + run == bytes.len()) {
+            (true, true) => 2,
+            (true, false) | (false, true) => 4,
+            (false, false) => 6,
+        };
+
+        if run >= threshold {
+            let mut run = run;
+            if run % 2 == 1 {
+                switch_set(&mut values, &mut set, CodeSet::B);
+                values.push((bytes[i] - 32) as usize);
+                i += 1;
+                run -= 1;
+            }
+            switch_set(&mut values, &mut set, CodeSet::C);
+            for pair in bytes[i..i + run].chunks(2) {
+                values.push(((pair[0] - b'0') * 10 + (pair[1] - b'0')) as usize);
+            }
+            i += run;
+        } else {
+            switch_set(&mut values, &mut set, CodeSet::B);
+            values.push((bytes[i] - 32) as usize);
+            i += 1;
+        }
+    }
+
+    let checksum = values.iter().enumerate().map(|(k, value)| k.max(1) * value).sum::<usize>() % 103;
+    values.push(checksum);
+    values.push(CODE128_STOP);
+
+    let mut modules = vec![];
+    for value in values {
+        for (k, width) in CODE128[value].bytes().enumerate() {
+            // Os elementos alternam barra e espaço, começando por barra
+            modules.extend(std::iter::repeat_n(k % 2 == 0, (width - b'0') as usize));
+        }
+    }
+    Ok(modules)
+}
+
+// Codificação "L" (paridade ímpar) dos dígitos do EAN-13; "R" é o complemento
+// e "G" é o "R" espelhado
+const EAN_L: [&str; 10] = [
+    "0001101", "0011001", "0010011", "0111101", "0100011",
+    "0110001", "0101111", "0111011", "0110111", "0001011",
+];
+
+// O primeiro dígito não é desenhado: ele escolhe L/G para os 6 dígitos seguintes
+const EAN_PARITY: [&str; 10] = [
+    "LLLLLL", "LLGLGG", "LLGGLG", "LLGGGL", "LGLLGG",
+    "LGGLLG", "LGGGLG", "LGGGGL", "LGLGLG", "LGLGGL",
+];
+
+fn ean13_check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits.iter().enumerate()
+        .map(|(i, d)| *d as u32 * if i % 2 == 0 { 1 } else { 3 })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+/// EAN-13 a partir de 12 dígitos (o verificador é calculado) ou 13 (é conferido).
+fn encode_ean13(text: &str) -> Result<Vec<bool>, AdgError> {
+    if !(text.len() == 12 || text.len() == 13) || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(AdgError::type_error(format!("qr: EAN-13 espera 12 ou 13 dígitos, recebeu \"{}\"", text)));
+    }
+    let mut digits: Vec<u8> = text.bytes().map(|b| b - b'0').collect();
+    let check = ean13_check_digit(&digits[..12]);
+    match digits.get(12) {
+        Some(&given) if given != check => {
+            return Err(AdgError::type_error(format!(
+                "qr: dígito verificador do EAN-13 inválido em \"{}\" (esperado {})", text, check,
+            )));
+        }
+        Some(_) => {}
+        None => digits.push(check),
+    }
+
+    let bits = |pattern: &str| pattern.bytes().map(|b| b == b'1').collect::<Vec<_>>();
+    let mut modules = bits("101");
+    for (i, parity) in EAN_PARITY[digits[0] as usize].bytes().enumerate() {
+        let l = bits(EAN_L[digits[i + 1] as usize]);
+        match parity {
+            b'L' => modules.extend(l),
+            _ => modules.extend(l.iter().rev().map(|dark| !dark)),
+        }
+    }
+    modules.extend(bits("01010"));
+    for &digit in &digits[7..] {
+        modules.extend(bits(EAN_L[digit as usize]).iter().map(|dark| !dark));
+    }
+    modules.extend(bits("101"));
+    Ok(modules)
+}
+
+fn pad_row(row: &[bool], margin: usize) -> Vec<bool> {
+    let mut padded = vec![false; margin];
+    padded.extend_from_slice(row);
+    padded.extend(std::iter::repeat_n(false, margin));
+    padded
+}
+
+fn pad_matrix(rows: &[Vec<bool>], margin: usize) -> Vec<Vec<bool>> {
+    let width = rows.first().map_or(0, Vec::len) + 2 * margin;
+    let mut padded = vec![vec![false; width]; margin];
+    padded.extend(rows.iter().map(|row| pad_row(row, margin)));
+    padded.extend(std::iter::repeat_n(vec![false; width], margin));
+    padded
+}
+
+// Sequências de módulos escuros: (início, tamanho)
+fn dark_runs(row: &[bool]) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = vec![];
+    for (x, dark) in row.iter().enumerate() {
+        match runs.last_mut() {
+            Some((start, len)) if *dark && *start + *len == x => *len += 1,
+            _ if *dark => runs.push((x, 1)),
+            _ => {}
+        }
+    }
+    runs
+}
+
+fn render_svg(symbol: &Symbol, options: &Options) -> String {
+    let mut path = String::new();
+    let (width, height, view_box, aspect) = match symbol {
+        Symbol::Matrix(rows) => {
+            let rows = pad_matrix(rows, options.margin);
+            let n = rows.len();
+            for (y, row) in rows.iter().enumerate() {
+                for (x, len) in dark_runs(row) {
+                    path.push_str(&format!("M{},{}h{}v1h-{}z", x, y, len, len));
+                }
+            }
+            let size = options.size.unwrap_or(n * 8);
+            (size, size, format!("0 0 {} {}", n, n), "")
+        }
+        Symbol::Linear(bars) => {
+            // Uma unidade de altura, esticada até `height`
+            let bars = pad_row(bars, options.margin);
+            for (x, len) in dark_runs(&bars) {
+                path.push_str(&format!("M{},0h{}v1h-{}z", x, len, len));
+            }
+            let width = options.size.unwrap_or(bars.len() * 2);
+            let height = options.height.unwrap_or(width / 2);
+            (width, height, format!("0 0 {} 1", bars.len()), " preserveAspectRatio=\"none\"")
+        }
+    };
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{}\"{} shape-rendering=\"crispEdges\">\
+<rect width=\"100%\" height=\"100%\" fill=\"#fff\"/><path fill=\"#000\" d=\"{}\"/></svg>",
+        width, height, view_box, aspect, path,
+    )
+}
+
+/// Imagem em tons de cinza (0 = escuro, 255 = claro); cada módulo vira um
+/// quadrado de pixels inteiros, então `size` é arredondado para baixo.
+fn render_pixels(symbol: &Symbol, options: &Options) -> Result<(usize, usize, Vec<u8>), AdgError> {
+    let pixel = |dark: bool| if dark { 0 } else { 255 };
+    let check = |width: usize, height: usize| {
+        if width * height > MAX_PIXELS {
+            return Err(AdgError::type_error(format!(
+                "qr.png: imagem grande demais ({}x{} pixels, o máximo é {})", width, height, MAX_PIXELS,
+            )));
+        }
+        Ok(())
+    };
+    match symbol {
+        Symbol::Matrix(rows) => {
+            let rows = pad_matrix(rows, options.margin);
+            let scale = options.size.map_or(8, |size| (size / rows.len()).max(1));
+            let side = rows.len() * scale;
+            check(side, side)?;
+            let pixels = (0..side * side).map(|i| pixel(rows[i / side / scale][i % side / scale])).collect();
+            Ok((side, side, pixels))
+        }
+        Symbol::Linear(bars) => {
+            let bars = pad_row(bars, options.margin);
+            let scale = options.size.map_or(2, |size| (size / bars.len()).max(1));
+            let width = bars.len() * scale;
+            let height = options.height.unwrap_or(width / 2);
+            check(width, height)?;
+            let line: Vec<u8> = (0..width).map(|x| pixel(bars[x / scale])).collect();
+            Ok((width, height, line.repeat(height)))
+        }
+    }
+}
+
+fn write_png(path: &str, (width, height, pixels): (usize, usize, Vec<u8>)) -> Result<(), String> {
+    let file = File::create(path).map_err(|err| err.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|err| err.to_string())?;
+    writer.write_image_data(&pixels).map_err(|err| err.to_string())
+}
+
+/// Desenha com blocos Unicode. Por padrão os módulos claros é que são pintados,
+/// para o código aparecer certo num terminal de fundo escuro; `invert` faz o
+/// contrário, para fundos claros.
+fn render_unicode(symbol: &Symbol, options: &Options) -> String {
+    let lit = |dark: bool| dark == options.invert;
+    match symbol {
+        // Duas linhas de módulos por linha de texto: ▀ em cima, ▄ embaixo
+        Symbol::Matrix(rows) => {
+            let rows = pad_matrix(rows, options.margin);
+            let light = vec![false; rows[0].len()];
+            rows.chunks(2)
+                .map(|pair| {
+                    let bottom = pair.get(1).unwrap_or(&light);
+                    pair[0].iter().zip(bottom)
+                        .map(|(top, bottom)| match (lit(*top), lit(*bottom)) {
+                            (true, true) => '█',
+                            (true, false) => '▀',
+                            (false, true) => '▄',
+                            (false, false) => ' ',
+                        })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        // Dois módulos por caractere: ▌ à esquerda, ▐ à direita
+        Symbol::Linear(bars) => {
+            let bars = pad_row(bars, options.margin);
+            let line: String = bars.chunks(2)
+                .map(|pair| match (lit(pair[0]), lit(*pair.get(1).unwrap_or(&false))) {
+                    (true, true) => '█',
+                    (true, false) => '▌',
+                    (false, true) => '▐',
+                    (false, false) => ' ',
+                })
+                .collect();
+            vec![line; options.height.unwrap_or(4)].join("\n")
+        }
+    }
+}
+
+fn text_and_options<'a>(args: &'a [Value], function: &str) -> Result<(String, Option<&'a Value>), AdgError> {
+    match args {
+        [text] => Ok((text.as_string(), None)),
+        [text, options] => Ok((text.as_string(), Some(options))),
+        _ => Err(AdgError::arity_error(format!("qr.{} espera 1 ou 2 argumentos", function))),
+    }
+}
+
+fn svg(args: Vec<Value>) -> Result<Value, AdgError> {
+    let (text, options_value) = text_and_options(&args, "svg")?;
+    let options = options(options_value, "svg")?;
+    Ok(Value::String(render_svg(&encode(&text, &options)?, &options)))
+}
+
+fn unicode(args: Vec<Value>) -> Result<Value, AdgError> {
+    let (text, options_value) = text_and_options(&args, "unicode")?;
+    let options = options(options_value, "unicode")?;
+    Ok(Value::String(render_unicode(&encode(&text, &options)?, &options)))
+}
+
+fn png(args: Vec<Value>) -> Result<Value, AdgError> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(AdgError::arity_error("qr.png espera 2 ou 3 argumentos"));
+    }
+    let (text, path) = (args[0].as_string(), args[1].as_string());
+    let options = options(args.get(2), "png")?;
+    let pixels = render_pixels(&encode(&text, &options)?, &options)?;
+    write_png(&path, pixels)
+        .map_err(|err| AdgError::io_error(format!("Erro ao escrever no arquivo {}: {}", path, err)))?;
+    Ok(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    fn call(name: &str, args: Vec<Value>) -> Result<Value, AdgError> {
+        match get_qr_module().get(name) {
+            Some(Value::NativeFunction(f)) => f(args),
+            _ => panic!("qr.{} not found", name),
+        }
+    }
+
+    fn opts(entries: &[(&str, Value)]) -> Value {
+        Value::object(entries.iter().map(|(key, value)| (key.to_string(), value.clone())).collect::<IndexMap<_, _>>())
+    }
+
+    fn text(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    // --- Leitura das imagens geradas de volta para módulos ---
+
+    // Recorta a zona de silêncio: o QR sempre tem módulos escuros nas bordas (os localizadores)
+    fn crop(rows: Vec<Vec<bool>>) -> Vec<Vec<bool>> {
+        let dark_rows: Vec<usize> = (0..rows.len()).filter(|&y| rows[y].contains(&true)).collect();
+        let (top, bottom) = (dark_rows[0], *dark_rows.last().unwrap());
+        let left = rows.iter().filter_map(|row| row.iter().position(|d| *d)).min().unwrap();
+        rows[top..=bottom].iter().map(|row| row[left..left + bottom - top + 1].to_vec()).collect()
+    }
+
+    fn trim(row: Vec<bool>) -> Vec<bool> {
+        let first = row.iter().position(|d| *d).unwrap();
+        let last = row.iter().rposition(|d| *d).unwrap();
+        row[first..=last].to_vec()
+    }
+
+    fn from_unicode(drawing: &str, invert: bool) -> Vec<Vec<bool>> {
+        let mut rows = vec![];
+        for line in drawing.lines() {
+            let (top, bottom): (Vec<bool>, Vec<bool>) = line.chars()
+                .map(|c| {
+                    let (top, bottom) = match c {
+                        '█' => (true, true),
+                        '▀' => (true, false),
+                        '▄' => (false, true),
+                        _ => (false, false),
+                    };
+                    (top == invert, bottom == invert)
+                })
+                .unzip();
+            rows.push(top);
+            rows.push(bottom);
+        }
+        rows
+    }
+
+    fn bars_from_unicode(drawing: &str) -> Vec<bool> {
+        drawing.lines().next().unwrap().chars()
+            .flat_map(|c| match c {
+                '█' => [false, false],
+                '▌' => [false, true],
+                '▐' => [true, false],
+                _ => [true, true],
+            })
+            .collect()
+    }
+
+    fn from_svg(svg: &str) -> Vec<Vec<bool>> {
+        let view_box = svg.split("viewBox=\"0 0 ").nth(1).unwrap();
+        let n: usize = view_box.split(' ').next().unwrap().parse().unwrap();
+        let mut rows = vec![vec![false; n]; n];
+        let path = svg.split(" d=\"").nth(1).unwrap();
+        for command in path.split('M').skip(1) {
+            let (position, rest) = command.split_once('h').unwrap();
+            let (x, y) = position.split_once(',').unwrap();
+            let (x, y): (usize, usize) = (x.parse().unwrap(), y.parse().unwrap());
+            let len: usize = rest.split('v').next().unwrap().parse().unwrap();
+            rows[y][x..x + len].fill(true);
+        }
+        rows
+    }
+
+    fn from_svg_bars(svg: &str) -> Vec<bool> {
+        let total: usize = svg.split("viewBox=\"0 0 ").nth(1).unwrap().split(' ').next().unwrap().parse().unwrap();
+        let mut bars = vec![false; total];
+        let path = svg.split(" d=\"").nth(1).unwrap();
+        for command in path.split('M').skip(1) {
+            let (x, rest) = command.split_once(",0h").unwrap();
+            let x: usize = x.parse().unwrap();
+            let len: usize = rest.split('v').next().unwrap().parse().unwrap();
+            bars[x..x + len].fill(true);
+        }
+        bars
+    }
+
+    fn read_png(path: &std::path::Path) -> (usize, usize, Vec<u8>) {
+        let decoder = png::Decoder::new(File::open(path).unwrap());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        pixels.truncate(info.buffer_size());
+        (info.width as usize, info.height as usize, pixels)
+    }
+
+    // A escala é a menor sequência de pixels iguais da primeira linha com barras
+    fn modules_in_row(pixels: &[u8]) -> (usize, Vec<bool>) {
+        let mut runs: Vec<(u8, usize)> = vec![];
+        for &p in pixels {
+            match runs.last_mut() {
+                Some((value, len)) if *value == p => *len += 1,
+                _ => runs.push((p, 1)),
+            }
+        }
+        let inner = &runs[1..runs.len() - 1];
+        let scale = inner.iter().map(|(_, len)| *len).min().unwrap();
+        let bars = inner.iter().flat_map(|(value, len)| std::iter::repeat_n(*value == 0, len / scale)).collect();
+        (scale, bars)
+    }
+
+    fn qr_from_png(path: &std::path::Path) -> Vec<Vec<bool>> {
+        let (width, height, pixels) = read_png(path);
+        // O canto do localizador tem 7 módulos escuros seguidos
+        let first = pixels.iter().position(|p| *p == 0).unwrap();
+        let run = pixels[first..].iter().take_while(|p| **p == 0).count();
+        let scale = run / 7;
+        (0..height / scale)
+            .map(|y| (0..width / scale).map(|x| pixels[(y * scale + scale / 2) * width + x * scale + scale / 2] == 0).collect())
+            .collect()
+    }
+
+    // --- Decodificadores, só para conferir o que foi gerado ---
+
+    // Blocos de correção por versão (1 a 10) e nível L, M, Q, H: (blocos, bytes de correção por bloco)
+    const QR_BLOCKS: [[(usize, usize); 4]; 10] = [
+        [(1, 7), (1, 10), (1, 13), (1, 17)],
+        [(1, 10), (1, 16), (1, 22), (1, 28)],
+        [(1, 15), (1, 26), (2, 18), (2, 22)],
+        [(1, 20), (2, 18), (2, 26), (4, 16)],
+        [(1, 26), (2, 24), (4, 18), (4, 22)],
+        [(2, 18), (4, 16), (4, 24), (4, 28)],
+        [(2, 20), (4, 18), (6, 18), (5, 26)],
+        [(2, 24), (4, 22), (6, 22), (6, 26)],
+        [(2, 30), (5, 22), (8, 20), (8, 24)],
+        [(4, 18), (5, 26), (8, 24), (8, 28)],
+    ];
+
+    fn alignment_positions(version: usize) -> Vec<usize> {
+        if version == 1 {
+            return vec![];
+        }
+        let count = version / 7 + 2;
+        let step = (version * 4 + count * 2 + 1) / (count * 2 - 2) * 2;
+        let mut positions = vec![6];
+        let mut pos = version * 4 + 10;
+        while positions.len() < count {
+            positions.insert(1, pos);
+            pos -= step;
+        }
+        positions
+    }
+
+    fn is_function(version: usize, x: usize, y: usize) -> bool {
+        let n = version * 4 + 17;
+        let finder = |a: usize, b: usize| a < 9 && b < 9;
+        if finder(x, y) || finder(n - 1 - x, y) && x >= n - 8 || finder(x, n - 1 - y) && y >= n - 8 {
+            return true;
+        }
+        if x == 6 || y == 6 {
+            return true;
+        }
+        if version >= 7 && (x >= n - 11 && y < 6 || y >= n - 11 && x < 6) {
+            return true;
+        }
+        let positions = alignment_positions(version);
+        let last = positions.len().saturating_sub(1);
+        positions.iter().enumerate().any(|(i, &cy)| positions.iter().enumerate().any(|(j, &cx)| {
+            // Os cantos já são ocupados pelos localizadores
+            let corner = [(0, 0), (0, last), (last, 0)].contains(&(i, j));
+            !corner && x.abs_diff(cx) <= 2 && y.abs_diff(cy) <= 2
+        }))
+    }
+
+    fn decode_qr(modules: &[Vec<bool>]) -> String {
+        let n = modules.len();
+        let version = (n - 17) / 4;
+        assert!((1..=10).contains(&version), "test decoder only handles versions 1-10");
+        let at = |x: usize, y: usize| modules[y][x];
+
+        // Formato: nível de correção e máscara, lidos em volta do localizador de cima à esquerda
+        let coords = [(0, 8), (1, 8), (2, 8), (3, 8), (4, 8), (5, 8), (7, 8), (8, 8), (8, 7), (8, 5), (8, 4), (8, 3), (8, 2), (8, 1), (8, 0)];
+        let format = coords.iter().fold(0u16, |acc, &(x, y)| acc << 1 | at(x, y) as u16) ^ 0x5412;
+        let level = match format >> 13 {
+            0b01 => 0,
+            0b00 => 1,
+            0b11 => 2,
+            _ => 3,
+        };
+        let mask = (format >> 10) & 7;
+        let masked = |x: usize, y: usize| match mask {
+            0 => (x + y).is_multiple_of(2),
+            1 => y.is_multiple_of(2),
+            2 => x.is_multiple_of(3),
+            3 => (x + y).is_multiple_of(3),
+            4 => (y / 2 + x / 3).is_multiple_of(2),
+            5 => (x * y) % 2 + (x * y) % 3 == 0,
+            6 => ((x * y) % 2 + (x * y) % 3).is_multiple_of(2),
+            _ => ((x + y) % 2 + (x * y) % 3).is_multiple_of(2),
+        };
+
+        // Zigue-zague de baixo para cima, em pares de colunas da direita para a esquerda
+        let mut bits = vec![];
+        let mut right = n as i64 - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for i in 0..n {
+                let y = if upward { n - 1 - i } else { i };
+                for x in [right as usize, right as usize - 1] {
+                    if !is_function(version, x, y) {
+                        bits.push(at(x, y) != masked(x, y));
+                    }
+                }
+            }
+            right -= 2;
+        }
+        let codewords: Vec<u8> = bits.chunks(8).filter(|c| c.len() == 8)
+            .map(|byte| byte.iter().fold(0, |acc, bit| acc << 1 | *bit as u8))
+            .collect();
+
+        // Desfaz o entrelaçamento dos blocos; a correção de erros não é necessária
+        let (blocks, ec_per_block) = QR_BLOCKS[version - 1][level];
+        let total = codewords.len();
+        let short_blocks = blocks - total % blocks;
+        let short_data = total / blocks - ec_per_block;
+        let mut data = vec![vec![]; blocks];
+        let mut next = codewords.iter();
+        for i in 0..=short_data {
+            for (k, block) in data.iter_mut().enumerate() {
+                if i < short_data + (k >= short_blocks) as usize {
+                    block.push(*next.next().unwrap());
+                }
+            }
+        }
+        let data: Vec<bool> = data.concat().iter().flat_map(|byte| (0..8).rev().map(move |i| byte >> i & 1 == 1)).collect();
+
+        let mut bits = data.into_iter();
+        let mut read = |count: usize| -> Option<usize> {
+            (0..count).try_fold(0, |acc, _| Some(acc << 1 | bits.next()? as usize))
+        };
+        let alphanumeric = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+        let mut bytes = vec![];
+        // O terminador pode faltar quando os dados enchem o código
+        while let Some(mode) = read(4) {
+            match mode {
+                0b0001 => {
+                    let mut count = read(if version < 10 { 10 } else { 12 }).unwrap();
+                    while count > 0 {
+                        let digits = count.min(3);
+                        let value = read([4, 7, 10][digits - 1]).unwrap();
+                        bytes.extend(format!("{:0width$}", value, width = digits).bytes());
+                        count -= digits;
+                    }
+                }
+                0b0010 => {
+                    let mut count = read(if version < 10 { 9 } else { 11 }).unwrap();
+                    while count > 0 {
+                        if count >= 2 {
+                            let value = read(11).unwrap();
+                            bytes.extend([alphanumeric[value / 45], alphanumeric[value % 45]]);
+                            count -= 2;
+                        } else {
+                            bytes.push(alphanumeric[read(6).unwrap()]);
+                            count -= 1;
+                        }
+                    }
+                }
+                0b0100 => {
+                    let count = read(if version < 10 { 8 } else { 16 }).unwrap();
+                    for _ in 0..count {
+                        bytes.push(read(8).unwrap() as u8);
+                    }
+                }
+                0b0000 => break,
+                mode => panic!("unsupported QR mode {:04b}", mode),
+            }
+        }
+        String::from_utf8(bytes).unwrap()
+    }
+
+    fn decode_code128(modules: &[bool]) -> String {
+        let widths: Vec<String> = modules.chunk_by(|a, b| a == b).map(|run| run.len().to_string()).collect();
+        let (symbols, stop) = widths.split_at(widths.len() - 7);
+        assert_eq!(stop.concat(), CODE128[CODE128_STOP]);
+
+        let values: Vec<usize> = symbols.chunks(6)
+            .map(|pattern| CODE128.iter().position(|p| *p == pattern.concat()).expect("unknown Code128 symbol"))
+            .collect();
+        let (checksum, values) = values.split_last().unwrap();
+        let expected = values.iter().enumerate().map(|(k, value)| k.max(1) * value).sum::<usize>() % 103;
+        assert_eq!(*checksum, expected, "Code128 checksum");
+
+        let mut set = if values[0] == CODE128_START_C { CodeSet::C } else { CodeSet::B };
+        let mut text = String::new();
+        for &value in &values[1..] {
+            match (set, value) {
+                (_, CODE128_CODE_B) => set = CodeSet::B,
+                (_, CODE128_CODE_C) => set = CodeSet::C,
+                (CodeSet::B, value) => text.push((value as u8 + 32) as char),
+                (CodeSet::C, value) => text.push_str(&format!("{:02}", value)),
+            }
+        }
+        text
+    }
+
+    fn decode_ean13(modules: &[bool]) -> String {
+        assert_eq!(modules.len(), 95);
+        let pattern = |range: std::ops::Range<usize>| -> String {
+            modules[range].iter().map(|dark| if *dark { '1' } else { '0' }).collect()
+        };
+        assert_eq!((pattern(0..3), pattern(45..50), pattern(92..95)), ("101".into(), "01010".into(), "101".into()));
+
+        let mut digits = String::new();
+        let mut parity = String::new();
+        for i in 0..6 {
+            let code = pattern(3 + i * 7..10 + i * 7);
+            let g: String = code.chars().rev().map(|c| if c == '1' { '0' } else { '1' }).collect();
+            let (digit, kind) = match EAN_L.iter().position(|l| *l == code) {
+                Some(digit) => (digit, 'L'),
+                None => (EAN_L.iter().position(|l| *l == g).expect("unknown EAN digit"), 'G'),
+            };
+            digits.push((b'0' + digit as u8) as char);
+            parity.push(kind);
+        }
+        for i in 0..6 {
+            let code: String = pattern(50 + i * 7..57 + i * 7).chars().map(|c| if c == '1' { '0' } else { '1' }).collect();
+            digits.push((b'0' + EAN_L.iter().position(|l| *l == code).expect("unknown EAN digit") as u8) as char);
+        }
+        let first = EAN_PARITY.iter().position(|p| *p == parity).expect("unknown EAN parity");
+        format!("{}{}", first, digits)
+    }
+
+    #[test]
+    fn qr_codes_round_trip_through_every_format() {
+        let texts = [
+            "HELLO WORLD",
+            "0123456789012345",
+            "https://ingressos.exemplo.com/checkin?id=48213&evento=adg-conf",
+            "Ingresso nº 42 — Pista, Lote 1 ✓",
+        ];
+        let png_path = std::env::temp_dir().join(format!("adg-qr-{}.png", std::process::id()));
+
+        for (i, content) in texts.iter().enumerate() {
+            let ec = ["L", "M", "Q", "H"][i % 4];
+            let options = opts(&[("ec", text(ec))]);
+
+            let svg = call("svg", vec![text(content), options.clone()]).unwrap().as_string();
+            assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""), "{}", svg);
+            assert_eq!(decode_qr(&crop(from_svg(&svg))), *content);
+
+            let drawing = call("unicode", vec![text(content), options.clone()]).unwrap().as_string();
+            assert_eq!(decode_qr(&crop(from_unicode(&drawing, false))), *content);
+            let inverted = call("unicode", vec![text(content), opts(&[("ec", text(ec)), ("invert", Value::Boolean(true))])]).unwrap();
+            assert_eq!(decode_qr(&crop(from_unicode(&inverted.as_string(), true))), *content);
+
+            call("png", vec![text(content), text(png_path.to_str().unwrap()), options]).unwrap();
+            assert_eq!(decode_qr(&crop(qr_from_png(&png_path))), *content);
+        }
+        std::fs::remove_file(&png_path).unwrap();
+    }
+
+    #[test]
+    fn qr_options_control_size_and_margin() {
+        // "HELLO WORLD" com nível M cabe na versão 1: 21 módulos, mais 4 de margem de cada lado
+        let svg = call("svg", vec![text("HELLO WORLD")]).unwrap().as_string();
+        assert!(svg.contains("width=\"232\" height=\"232\" viewBox=\"0 0 29 29\""), "{}", svg);
+        let svg = call("svg", vec![text("HELLO WORLD"), opts(&[("size", Value::Number(100.0)), ("margin", Value::Number(0.0))])]).unwrap();
+        assert!(svg.as_string().contains("width=\"100\" height=\"100\" viewBox=\"0 0 21 21\""));
+
+        let drawing = call("unicode", vec![text("HELLO WORLD")]).unwrap().as_string();
+        assert_eq!(drawing.lines().count(), 15);
+        assert!(drawing.lines().all(|line| line.chars().count() == 29));
+        assert!(drawing.starts_with("█████"));
+
+        let (width, height, _) = render_pixels(&encode("HELLO WORLD", &options(None, "png").unwrap()).unwrap(), &options(Some(&opts(&[("size", Value::Number(100.0))])), "png").unwrap()).unwrap();
+        assert_eq!((width, height), (87, 87));
+
+        for bad in [
+            opts(&[("ec", text("X"))]),
+            opts(&[("type", text("pdf417"))]),
+            opts(&[("size", Value::Number(0.0))]),
+            opts(&[("size", Value::Number(1e9))]),
+            opts(&[("height", Value::Number(10_001.0))]),
+            opts(&[("margin", Value::Number(-1.0))]),
+            opts(&[("margin", Value::Number(1e9))]),
+            opts(&[("cor", text("azul"))]),
+            text("H"),
+        ] {
+            assert!(call("svg", vec![text("a"), bad]).is_err());
+        }
+        assert!(call("svg", vec![text(&"x".repeat(3000)), opts(&[("ec", text("H"))])]).is_err());
+        assert!(call("svg", vec![]).is_err());
+        assert!(call("png", vec![text("a"), text("/nao/existe/qr.png")]).is_err());
+
+        // Cada opção cabe no limite, mas a imagem inteira não: 29 módulos de 344 pixels
+        let err = call("png", vec![text("a"), text("/nao/existe/qr.png"), opts(&[("size", Value::Number(10_000.0))])]).unwrap_err();
+        assert_eq!(err.message(), "qr.png: imagem grande demais (9976x9976 pixels, o máximo é 40000000)");
+        let huge = call("png", vec![text("a"), text("/nao/existe/qr.png"), opts(&[("size", Value::Number(1e9))])]).unwrap_err();
+        assert_eq!(huge.message(), "qr.png: \"size\" deve ser um inteiro entre 1 e 10000");
+    }
+
+    #[test]
+    fn code128_round_trips_and_packs_digits() {
+        for content in ["ADG-2024", "Ingresso #42", "12345678", "ABC123456789X", "Lote 7", "1"] {
+            let options = opts(&[("type", text("code128"))]);
+            let svg = call("svg", vec![text(content), options.clone()]).unwrap().as_string();
+            assert_eq!(decode_code128(&trim(from_svg_bars(&svg))), content);
+
+            let drawing = call("unicode", vec![text(content), options]).unwrap().as_string();
+            assert_eq!(decode_code128(&trim(bars_from_unicode(&drawing))), content);
+        }
+
+        // Só dígitos: início direto no conjunto C, dois dígitos por símbolo
+        let digits = encode_code128("12345678").unwrap();
+        assert_eq!(digits.len(), 11 * 6 + 13);
+        assert!(encode_code128("").is_err());
+        assert!(encode_code128("café").is_err());
+    }
+
+    #[test]
+    fn code128_table_is_well_formed() {
+        // Cada símbolo ocupa 11 módulos (o de parada, 13) e as barras somam um número par
+        for (value, pattern) in CODE128.iter().enumerate() {
+            let widths: Vec<usize> = pattern.bytes().map(|b| (b - b'0') as usize).collect();
+            assert_eq!(widths.iter().sum::<usize>(), if value == CODE128_STOP { 13 } else { 11 }, "{}", value);
+            assert_eq!(widths.iter().step_by(2).sum::<usize>() % 2, 0, "{}", value);
+            assert_eq!(CODE128.iter().filter(|p| *p == pattern).count(), 1, "{}", value);
+        }
+    }
+
+    #[test]
+    fn ean13_round_trips_and_checks_the_check_digit() {
+        // 4006381333931: exemplo clássico, com dígito verificador 1
+        let png_path = std::env::temp_dir().join(format!("adg-ean-{}.png", std::process::id()));
+        for content in ["4006381333931", "789123456789", "0000000000000"] {
+            let options = opts(&[("type", text("ean13"))]);
+            call("png", vec![text(content), text(png_path.to_str().unwrap()), options.clone()]).unwrap();
+            let (width, _, pixels) = read_png(&png_path);
+            let (scale, bars) = modules_in_row(&pixels[..width]);
+            assert_eq!(scale, 2);
+            let expected = if content.len() == 12 { format!("{}5", content) } else { content.to_string() };
+            assert_eq!(decode_ean13(&trim(bars)), expected);
+
+            let drawing = call("unicode", vec![text(content), options]).unwrap().as_string();
+            assert_eq!(decode_ean13(&trim(bars_from_unicode(&drawing))), expected);
+        }
+        std::fs::remove_file(&png_path).unwrap();
+
+        assert_eq!(ean13_check_digit(&[4, 0, 0, 6, 3, 8, 1, 3, 3, 3, 9, 3]), 1);
+        for bad in ["4006381333932", "12345", "40063813339a1"] {
+            assert!(encode_ean13(bad).is_err(), "{}", bad);
+        }
+    }
+}
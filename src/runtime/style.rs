@@ -1,67 +1,68 @@
 use std::collections::HashMap;
+use crate::error::AdgError;
 use crate::interpreter::Value;
 
 // Cada função abaixo é um ponteiro de função compatível com NativeFunction
 
-fn red(args: Vec<Value>) -> Value {
+fn red(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("31", args)
 }
 
-fn green(args: Vec<Value>) -> Value {
+fn green(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("32", args)
 }
 
-fn yellow(args: Vec<Value>) -> Value {
+fn yellow(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("33", args)
 }
 
-fn blue(args: Vec<Value>) -> Value {
+fn blue(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("34", args)
 }
 
-fn magenta(args: Vec<Value>) -> Value {
+fn magenta(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("35", args)
 }
 
-fn cyan(args: Vec<Value>) -> Value {
+fn cyan(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("36", args)
 }
 
-fn white(args: Vec<Value>) -> Value {
+fn white(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("37", args)
 }
 
-fn bold(args: Vec<Value>) -> Value {
+fn bold(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("1", args)
 }
 
-fn underline(args: Vec<Value>) -> Value {
+fn underline(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("4", args)
 }
 
-fn bg_red(args: Vec<Value>) -> Value {
+fn bg_red(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("41", args)
 }
 
-fn bg_green(args: Vec<Value>) -> Value {
+fn bg_green(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("42", args)
 }
 
-fn bg_yellow(args: Vec<Value>) -> Value {
+fn bg_yellow(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("43", args)
 }
 
-fn bg_blue(args: Vec<Value>) -> Value {
+fn bg_blue(args: Vec<Value>) -> Result<Value, AdgError> {
     apply_ansi("44", args)
 }
 
 // Função auxiliar comum
-fn apply_ansi(code: &str, args: Vec<Value>) -> Value {
+fn apply_ansi(code: &str, args: Vec<Value>) -> Result<Value, AdgError> {
     if args.len() != 1 {
-        panic!("style.{} espera 1 argumento", code);
+        return Err(AdgError::arity_error(format!("style.{} espera 1 argumento", code)));
     }
     let text = args[0].as_string();
-    Value::String(format!("\x1b[{}m{}\x1b[0m", code, text))
+    Ok(Value::String(format!("\x1b[{}m{}\x1b[0m", code, text)))
 }
 
 // Registrar tudo
@@ -0,0 +1,118 @@
+/// Trecho do código-fonte: `start..end` em bytes, mais a linha e a coluna
+/// (contadas a partir de 1) onde o trecho começa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Default for Span {
+    fn default() -> Self {
+        Span { start: 0, end: 0, line: 1, column: 1 }
+    }
+}
+
+impl Span {
+    // Do início de `self` até o fim de `other`
+    pub fn to(self, other: Span) -> Span {
+        Span { end: other.end.max(self.end), ..self }
+    }
+}
+
+/// Arquivos (ou entradas do REPL) carregados numa execução. Cada um ocupa um
+/// intervalo próprio de deslocamentos, então um `Span` também identifica o arquivo
+/// de onde veio.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+    next_offset: usize,
+}
+
+#[derive(Debug)]
+struct SourceFile {
+    path: String,
+    text: String,
+    start: usize,
+}
+
+impl SourceMap {
+    /// Registra o código e devolve a origem a ser passada ao `Lexer::with_origin`.
+    pub fn add(&mut self, path: &str, text: &str) -> Span {
+        let start = self.next_offset;
+        // +1 separa arquivos vizinhos: o fim de um não coincide com o início do outro
+        self.next_offset += text.len() + 1;
+        self.files.push(SourceFile { path: path.to_string(), text: text.to_string(), start });
+        Span { start, end: start, line: 1, column: 1 }
+    }
+
+    /// Caminho e código do arquivo que contém o trecho, e o trecho relativo a ele.
+    pub fn locate(&self, span: Span) -> Option<(&str, &str, Span)> {
+        let file = self.files.iter()
+            .find(|file| (file.start..=file.start + file.text.len()).contains(&span.start))?;
+        let local = Span { start: span.start - file.start, end: span.end - file.start, ..span };
+        Some((&file.path, &file.text, local))
+    }
+}
+
+/// Formata um erro como `arquivo:linha:coluna`, mostrando a linha do código
+/// com o trecho problemático sublinhado.
+pub fn render(path: &str, source: &str, span: Span, message: &str) -> String {
+    render_as("erro", path, source, span, message)
+}
+
+/// Como `render`, com outro rótulo no lugar de `erro` (por exemplo, `aviso`).
+pub fn render_as(label: &str, path: &str, source: &str, span: Span, message: &str) -> String {
+    let start = span.start.min(source.len());
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[start..].find('\n').map_or(source.len(), |i| start + i);
+    let text = source[line_start..line_end].trim_end_matches('\r');
+
+    // Tabs são mantidos para que o sublinhado fique alinhado com o texto
+    let indent: String = source[line_start..start].chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let end = span.end.clamp(start, line_end);
+    let width = source[start..end].chars().count().max(1);
+
+    let line = span.line.to_string();
+    let pad = " ".repeat(line.len());
+    format!(
+        "{label}: {message}\n{pad}--> {path}:{line}:{column}\n{pad} |\n{line} | {text}\n{pad} | {indent}{carets}",
+        column = span.column,
+        carets = "^".repeat(width),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_underlines_the_span_on_its_line() {
+        let source = "let a = 1;\n\tlet b = a - \"x\";\n";
+        let span = Span { start: 20, end: 27, line: 2, column: 10 };
+
+        assert_eq!(render("teste.adg", source, span, "TypeError: Expected number, got string"), [
+            "erro: TypeError: Expected number, got string",
+            " --> teste.adg:2:10",
+            "  |",
+            "2 | \tlet b = a - \"x\";",
+            "  | \t        ^^^^^^^",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn source_map_locates_spans_in_their_file() {
+        let mut sources = SourceMap::default();
+        let first = sources.add("a.adg", "let a = 1;");
+        let second = sources.add("b.adg", "let b = 2;\nb;");
+
+        assert_eq!(first.start, 0);
+        let span = Span { start: second.start + 11, end: second.start + 12, line: 2, column: 1 };
+        assert_eq!(sources.locate(span), Some(("b.adg", "let b = 2;\nb;", Span { start: 11, end: 12, line: 2, column: 1 })));
+        assert_eq!(sources.locate(Span { start: 4, end: 5, line: 1, column: 5 }).unwrap().0, "a.adg");
+        assert_eq!(sources.locate(Span { start: 1000, end: 1001, line: 1, column: 1 }), None);
+    }
+}
@@ -0,0 +1,187 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use crate::error::AdgError;
+use crate::interpreter::{Interpreter, Value};
+use crate::lexer::Lexer;
+use crate::parser::{Parser, StmtKind};
+
+/// Resultado de uma função `test_*`: `error` é `None` se ela passou.
+pub struct TestResult {
+    pub name: String,
+    pub error: Option<AdgError>,
+}
+
+/// Roda um arquivo de teste: primeiro o código do topo, depois cada função
+/// `test_*`, na ordem em que foram declaradas. Um erro de sintaxe ou no código
+/// do topo impede que os testes rodem. Os erros são localizados com
+/// `interpreter.sources()`.
+pub fn run_tests(interpreter: &mut Interpreter, path: &str, code: &str) -> Result<Vec<TestResult>, Vec<AdgError>> {
+    let stmts = Parser::new(Lexer::new(code)).parse()?;
+    let names: Vec<String> = stmts.iter()
+        .filter_map(|stmt| match &stmt.kind {
+            StmtKind::Function { name, .. } if name.starts_with("test_") => Some(name.clone()),
+            StmtKind::Export(decl) => match &decl.kind {
+                StmtKind::Function { name, .. } if name.starts_with("test_") => Some(name.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    interpreter.run_file(path, code)?;
+
+    Ok(names.into_iter()
+        .map(|name| {
+            // Declarada no topo, então sempre existe (a menos que o script a sobrescreva)
+            let function = interpreter.lookup(&name).unwrap_or(Value::Null);
+            let error = interpreter.call(function, vec![]).err();
+            TestResult { name, error }
+        })
+        .collect())
+}
+
+/// Arquivos `*_test.adg` dentro dos caminhos dados; arquivos citados
+/// explicitamente entram mesmo sem o sufixo.
+pub fn discover(paths: &[String]) -> Vec<PathBuf> {
+    let mut files = vec![];
+    for path in paths {
+        let path = Path::new(path);
+        if path.is_dir() {
+            collect(path, &mut files);
+        } else {
+            files.push(path.to_path_buf());
+        }
+    }
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Pastas ocultas e a saída do cargo nunca têm testes da linguagem
+        if name.starts_with('.') || name == "target" {
+            continue;
+        }
+        if path.is_dir() {
+            collect(&path, files);
+        } else if name.ends_with("_test.adg") {
+            files.push(path);
+        }
+    }
+}
+
+/// `adg test`: roda os testes encontrados e mostra um resumo.
+pub fn run(paths: &[String]) -> ExitCode {
+    let default = [".".to_string()];
+    let files = discover(if paths.is_empty() { &default } else { paths });
+    if files.is_empty() {
+        println!("Nenhum arquivo de teste (*_test.adg) encontrado.");
+        return ExitCode::SUCCESS;
+    }
+
+    let (mut passed, mut failed) = (0, 0);
+    for file in files {
+        let path = file.display().to_string();
+        let code = match fs::read_to_string(&file) {
+            Ok(code) => code,
+            Err(err) => {
+                eprintln!("erro: não foi possível ler o arquivo {}: {}", path, err);
+                return ExitCode::from(2);
+            }
+        };
+
+        let mut interpreter = Interpreter::new();
+        match run_tests(&mut interpreter, &path, &code) {
+            Ok(results) => {
+                for result in results {
+                    match result.error {
+                        None => {
+                            passed += 1;
+                            println!("ok      {} > {}", path, result.name);
+                        }
+                        Some(err) => {
+                            failed += 1;
+                            println!("FALHOU  {} > {}", path, result.name);
+                            eprintln!("{}\n", err.report_in(interpreter.sources()));
+                        }
+                    }
+                }
+            }
+            Err(errors) => {
+                failed += 1;
+                println!("FALHOU  {}", path);
+                let reports: Vec<String> = errors.iter().map(|err| err.report_in(interpreter.sources())).collect();
+                eprintln!("{}\n", reports.join("\n\n"));
+            }
+        }
+    }
+
+    println!("\nresultado: {} ok, {} falha(s)", passed, failed);
+    if failed == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_test_functions_in_declaration_order() {
+        let results = run_tests(&mut Interpreter::new(), "dobro_test.adg", r#"
+            fn dobro(n) { return n * 2; }
+
+            fn test_dobro() {
+                assert_eq(dobro(2), 4);
+            }
+
+            fn auxiliar() { throw "não é um teste"; }
+
+            fn test_falha() {
+                assert_eq(dobro(2), 5);
+            }
+
+            export fn test_erro() {
+                return 1 - "a";
+            }
+        "#).unwrap_or_else(|errors| panic!("{}", errors[0]));
+
+        let outcomes: Vec<(String, Option<String>)> = results.into_iter()
+            .map(|result| (result.name, result.error.map(|err| err.to_string())))
+            .collect();
+        assert_eq!(outcomes, vec![
+            ("test_dobro".to_string(), None),
+            ("test_falha".to_string(), Some("AssertionError: Expected 5, got 4".to_string())),
+            ("test_erro".to_string(), Some("TypeError: Expected number, got string".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn failures_outside_tests_stop_the_file() {
+        assert!(run_tests(&mut Interpreter::new(), "a_test.adg", "fn test_a() {").is_err());
+
+        let errors = run_tests(&mut Interpreter::new(), "a_test.adg", "throw \"setup\"; fn test_a() {}").err().unwrap();
+        assert_eq!(errors[0].to_string(), "Uncaught setup");
+    }
+
+    #[test]
+    fn discovers_test_files_recursively() {
+        let root = std::env::temp_dir().join(format!("adg-testing-{}", std::process::id()));
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::create_dir_all(root.join(".oculto")).unwrap();
+        for file in ["a_test.adg", "main.adg", "sub/b_test.adg", ".oculto/c_test.adg"] {
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        let found = discover(&[root.to_str().unwrap().to_string()]);
+        assert_eq!(found, vec![root.join("a_test.adg"), root.join("sub/b_test.adg")]);
+
+        let explicit = discover(&[root.join("main.adg").to_str().unwrap().to_string()]);
+        assert_eq!(explicit, vec![root.join("main.adg")]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}